@@ -17,14 +17,14 @@ pub enum DieValue {
     Six = 6,
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 #[wasm_bindgen]
 pub enum Player {
     Player1,
     Player2,
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum GamePhase {
     Rolling,
     Placing,
@@ -38,9 +38,15 @@ pub enum ColumnIndex {
     Two = 2,
 }
 
+/// Faces on a standard die
+const DEFAULT_DIE_FACES: u8 = 6;
+
+/// Largest die supported for variants (d8, d10, d12); sizes the scoring histograms
+const MAX_DIE_FACES: u8 = 12;
+
 // Compact representation: 3 columns × 3 rows = 9 slots per grid
-// Each slot: 0 = empty, 1-6 = die value
-#[derive(Clone, Debug, PartialEq, Eq)]
+// Each slot: 0 = empty, 1-die_faces = die value
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub struct Grid {
     data: [u8; 9], // 3 columns × 3 rows
 }
@@ -50,11 +56,24 @@ impl Grid {
         Grid { data: [0; 9] }
     }
 
+    /// Build a grid from a flat column-major slice, zero-filling any missing cells
+    fn from_slice(cells: &[u8]) -> Self {
+        let mut grid = Grid::new();
+        let len = cells.len().min(9);
+        grid.data[..len].copy_from_slice(&cells[..len]);
+        grid
+    }
+
     #[inline]
     fn get(&self, col: usize, row: usize) -> u8 {
         self.data[col * 3 + row]
     }
 
+    #[inline]
+    fn column(&self, col: usize) -> [u8; 3] {
+        [self.get(col, 0), self.get(col, 1), self.get(col, 2)]
+    }
+
     #[inline]
     fn set(&mut self, col: usize, row: usize, value: u8) {
         self.data[col * 3 + row] = value;
@@ -67,12 +86,7 @@ impl Grid {
 
     #[inline]
     fn get_empty_row(&self, col: usize) -> Option<usize> {
-        for row in 0..3 {
-            if self.get(col, row) == 0 {
-                return Some(row);
-            }
-        }
-        None
+        (0..3).find(|&row| self.get(col, row) == 0)
     }
 
     fn place_die(&mut self, col: usize, value: u8) -> bool {
@@ -98,9 +112,22 @@ impl Grid {
                 removed += 1;
             }
         }
-        // Write back (fill rest with zeros)
-        for row in 0..3 {
-            self.set(col, row, if row < idx { new_col[row] } else { 0 });
+        // Write back (new_col is already zero-filled past idx)
+        for (row, &v) in new_col.iter().enumerate() {
+            self.set(col, row, v);
+        }
+        removed
+    }
+
+    /// Clear a single cell and shift the dice above it down, returning the
+    /// value removed (0 for an empty cell)
+    fn clear_cell(&mut self, col: usize, row: usize) -> u8 {
+        let removed = self.get(col, row);
+        if removed != 0 {
+            for r in row..2 {
+                self.set(col, r, self.get(col, r + 1));
+            }
+            self.set(col, 2, 0);
         }
         removed
     }
@@ -108,6 +135,23 @@ impl Grid {
     fn is_full(&self) -> bool {
         self.data.iter().all(|&v| v != 0)
     }
+    
+    /// Number of dice of each value across the whole grid (index 0 unused)
+    fn value_counts(&self) -> [u8; MAX_DIE_FACES as usize + 1] {
+        let mut counts = [0u8; MAX_DIE_FACES as usize + 1];
+        for &v in self.data.iter().filter(|&&v| v != 0 && v <= MAX_DIE_FACES) {
+            counts[v as usize] += 1;
+        }
+        counts
+    }
+    
+    /// Whether every column is filled bottom-up with no die above an empty slot
+    fn respects_gravity(&self) -> bool {
+        (0..3).all(|col| {
+            let column = self.column(col);
+            column.windows(2).all(|pair| pair[0] != 0 || pair[1] == 0)
+        })
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -120,7 +164,31 @@ pub struct GameState {
     turn_number: u32,
 }
 
-#[derive(Clone, Copy, Debug)]
+// Positions compare and hash without `turn_number`, which doesn't affect their
+// value (matching the Zobrist key), so they can key memo tables directly
+impl PartialEq for GameState {
+    fn eq(&self, other: &Self) -> bool {
+        self.grid1 == other.grid1
+            && self.grid2 == other.grid2
+            && self.current_player == other.current_player
+            && self.current_die == other.current_die
+            && self.phase == other.phase
+    }
+}
+
+impl Eq for GameState {}
+
+impl std::hash::Hash for GameState {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.grid1.hash(state);
+        self.grid2.hash(state);
+        self.current_player.hash(state);
+        self.current_die.hash(state);
+        self.phase.hash(state);
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub struct DifficultyConfig {
     pub depth: u32,
     pub randomness: f64,
@@ -129,13 +197,272 @@ pub struct DifficultyConfig {
     pub advanced_eval: bool,
     pub adversarial: bool,
     pub time_budget_ms: f64,
+    /// Floor on the search depth, so a depth-0 (greedy) config still looks ahead
+    pub min_depth: u32,
+    /// Also score the opponent's attack potential and vulnerability in the
+    /// advanced evaluation's positional term, not just our own
+    pub symmetric_positional: bool,
+    /// Break ties between equally valued root moves by the raw score margin
+    /// after the move
+    pub basic_tiebreak: bool,
+    /// When modeled as an opponent, the chance of playing the searched move
+    /// rather than the greedy one (1.0 always searches)
+    pub skill: f64,
+    /// Weight of the penalty for spending a high die where it matches nothing
+    /// (0.0 disables it)
+    pub high_die_waste_weight: f64,
+    /// Single-player practice rules: placements never capture, and the search
+    /// maximizes the player's own grid score
+    pub solitaire: bool,
+    /// Bonus added to the player's placements in each column below the root,
+    /// e.g. the Master AI's learned attack bias
+    pub column_bias: [f64; 3],
+    /// Root moves within this much of the best value count as equal and are
+    /// split by a secondary criterion (0.0 disables)
+    pub equal_move_epsilon: f64,
+    /// Split equal root moves at random rather than by lowest exposure
+    pub equal_move_random: bool,
+    /// Split equal root moves by the opponent's worst-case capture first
+    pub safe_play: bool,
+    /// Value of still having moves to make in an unfinished position, scaled up
+    /// as the evaluating side's grid fills (0.0 disables)
+    pub tempo_bonus: f64,
+    /// When modeled as a searching opponent, the chance of a mistake per point
+    /// between their best and worst move (0.0 never errs), capped at
+    /// `MAX_BLUNDER_CHANCE`
+    pub blunder_per_point: f64,
+    /// What the search maximizes; ignored in solitaire
+    pub objective: SearchObjective,
+}
+
+/// Highest chance of a mistake a modeled opponent's mistake profile can reach
+const MAX_BLUNDER_CHANCE: f64 = 0.5;
+
+impl Default for DifficultyConfig {
+    fn default() -> Self {
+        DifficultyConfig {
+            depth: 0,
+            randomness: 0.0,
+            offense_weight: 0.5,
+            defense_weight: 0.5,
+            advanced_eval: false,
+            adversarial: false,
+            time_budget_ms: 0.0,
+            min_depth: 0,
+            symmetric_positional: false,
+            basic_tiebreak: false,
+            skill: 1.0,
+            high_die_waste_weight: 0.0,
+            solitaire: false,
+            column_bias: [0.0; 3],
+            equal_move_epsilon: 0.0,
+            equal_move_random: false,
+            safe_play: false,
+            tempo_bonus: 0.0,
+            blunder_per_point: 0.0,
+            objective: SearchObjective::ExpectedMargin,
+        }
+    }
+}
+
+impl DifficultyConfig {
+    /// Blend two configs for a continuous difficulty knob. Randomness, weights and
+    /// time budget are interpolated linearly, depth is interpolated and rounded,
+    /// and the flags come from `a` below t = 0.5 and from `b` from there on.
+    /// `t` is clamped to 0.0-1.0.
+    pub fn lerp(a: &DifficultyConfig, b: &DifficultyConfig, t: f64) -> DifficultyConfig {
+        let t = if t.is_finite() { t.clamp(0.0, 1.0) } else { 0.0 };
+        let mix = |x: f64, y: f64| x * (1.0 - t) + y * t;
+        let flags = if t < 0.5 { a } else { b };
+        DifficultyConfig {
+            depth: mix(a.depth as f64, b.depth as f64).round() as u32,
+            randomness: mix(a.randomness, b.randomness),
+            offense_weight: mix(a.offense_weight, b.offense_weight),
+            defense_weight: mix(a.defense_weight, b.defense_weight),
+            time_budget_ms: mix(a.time_budget_ms, b.time_budget_ms),
+            skill: mix(a.skill, b.skill),
+            high_die_waste_weight: mix(a.high_die_waste_weight, b.high_die_waste_weight),
+            column_bias: std::array::from_fn(|col| mix(a.column_bias[col], b.column_bias[col])),
+            equal_move_epsilon: mix(a.equal_move_epsilon, b.equal_move_epsilon),
+            tempo_bonus: mix(a.tempo_bonus, b.tempo_bonus),
+            blunder_per_point: mix(a.blunder_per_point, b.blunder_per_point),
+            min_depth: mix(a.min_depth as f64, b.min_depth as f64).round() as u32,
+            ..*flags
+        }
+    }
+    
+    /// The Beginner preset from the frontend's difficulty table
+    fn beginner() -> DifficultyConfig {
+        DifficultyConfig {
+            depth: 1,
+            randomness: 0.4,
+            offense_weight: 0.7,
+            defense_weight: 0.3,
+            ..DifficultyConfig::default()
+        }
+    }
+    
+    /// The Master preset from the frontend's difficulty table
+    fn master() -> DifficultyConfig {
+        DifficultyConfig {
+            depth: 6,
+            advanced_eval: true,
+            adversarial: true,
+            time_budget_ms: 100.0,
+            ..DifficultyConfig::default()
+        }
+    }
+    
+    /// Depth actually searched once the `min_depth` floor is applied
+    fn search_depth(&self) -> u32 {
+        self.depth.max(self.min_depth)
+    }
+}
+
+/// The quantity the search backs up through the tree
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[wasm_bindgen]
+pub enum SearchObjective {
+    /// Score margin, averaged through chance nodes
+    ExpectedMargin,
+    /// Chance of finishing ahead: 1.0 for a win, 0.5 for a draw and 0.0 for a
+    /// loss at terminals, and a logistic estimate from the evaluation at the
+    /// depth limit
+    WinProbability,
+}
+
+/// Points of evaluation that move a `WinProbability` leaf estimate from 50%
+/// to about 73%
+const WIN_PROBABILITY_SCALE: f64 = 10.0;
+
+/// Named opponent archetypes bundling coherent search and evaluation settings
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[wasm_bindgen]
+pub enum Personality {
+    /// Favors offense and chases captures
+    Aggressive,
+    /// Even offense and defense
+    Balanced,
+    /// Protects its own stacks and watches the opponent's threats
+    Defensive,
+    /// Plays a shallow game with frequent random moves
+    Chaotic,
+    /// Deep, cautious worst-case play that avoids risk
+    Grinder,
+}
+
+impl Personality {
+    /// The config this personality plays with
+    fn config(self) -> DifficultyConfig {
+        match self {
+            Personality::Aggressive => DifficultyConfig {
+                depth: 3,
+                randomness: 0.05,
+                offense_weight: 0.8,
+                defense_weight: 0.2,
+                advanced_eval: true,
+                ..DifficultyConfig::default()
+            },
+            Personality::Balanced => DifficultyConfig {
+                depth: 3,
+                advanced_eval: true,
+                ..DifficultyConfig::default()
+            },
+            Personality::Defensive => DifficultyConfig {
+                depth: 3,
+                offense_weight: 0.3,
+                defense_weight: 0.7,
+                advanced_eval: true,
+                symmetric_positional: true,
+                ..DifficultyConfig::default()
+            },
+            Personality::Chaotic => DifficultyConfig {
+                depth: 2,
+                randomness: 0.35,
+                offense_weight: 0.6,
+                defense_weight: 0.4,
+                ..DifficultyConfig::default()
+            },
+            Personality::Grinder => DifficultyConfig {
+                depth: 4,
+                offense_weight: 0.4,
+                defense_weight: 0.6,
+                advanced_eval: true,
+                adversarial: true,
+                symmetric_positional: true,
+                ..DifficultyConfig::default()
+            },
+        }
+    }
+}
+
+/// Maximum number of transposition table entries
+const TT_MAX_ENTRIES: usize = 100000;
+
+/// Share of `TT_MAX_ENTRIES` in use at the start of a move search above which
+/// entries the previous search didn't store or refresh are dropped
+const TT_AGING_FILL: f64 = 0.75;
+
+/// Default fraction of the node budget granted to the opponent model's sub-searches
+const DEFAULT_OPPONENT_NODE_SHARE: f64 = 0.5;
+
+/// Small deterministic generator (splitmix64) for reproducible games
+#[derive(Clone, Copy, Debug)]
+struct SeededRng {
+    state: u64,
+}
+
+impl SeededRng {
+    fn new(seed: u64) -> Self {
+        SeededRng { state: seed }
+    }
+    
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        splitmix64(self.state)
+    }
+    
+    /// Uniform random number in [0, 1)
+    fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+    
+    /// Uniform index in 0..n (n > 0)
+    fn below(&mut self, n: usize) -> usize {
+        ((self.next_f64() * n as f64) as usize).min(n - 1)
+    }
+}
+
+/// What a transposition table entry stores
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum NodeKind {
+    /// Backed-up value of a max or min node
+    Value,
+    /// Column the modeled opponent chose at a min node
+    OpponentDecision,
 }
 
 // Transposition table entry
-#[derive(Clone)]
+#[derive(Clone, Copy)]
 struct TTEntry {
     depth: u32,
     value: f64,
+    kind: NodeKind,
+    best_col: u8,
+    // Move search that last stored or refreshed the entry, for aging
+    generation: u8,
+}
+
+impl TTEntry {
+    fn value(depth: u32, value: f64) -> Self {
+        TTEntry { depth, value, kind: NodeKind::Value, best_col: 0, generation: 0 }
+    }
+    
+    /// A modeled opponent's chosen column, with the spread between their best
+    /// and worst move values kept in `value`
+    fn opponent_decision(depth: u32, col: usize, spread: f64) -> Self {
+        TTEntry { depth, value: spread, kind: NodeKind::OpponentDecision, best_col: col as u8, generation: 0 }
+    }
 }
 
 // Search context passed through the tree
@@ -143,29 +470,206 @@ struct SearchContext {
     tt: HashMap<u64, TTEntry>,
     nodes_explored: u32,
     max_nodes: u32,
+    // Nodes spent inside the opponent model's own expectimax, budgeted separately
+    // so a deep opponent model can't starve the primary search
+    opponent_nodes: u32,
+    opponent_node_share: f64,
+    opponent_model_depth: u32,
+    // Re-search the root at a uniform reduced depth when the node cap is hit
+    fair_root_on_cap: bool,
+    // After a capped search, check its move against a complete shallower one
+    check_cap_reliability: bool,
+    // Per-ply scaling of terminal values; 0 leaves win/loss values flat
+    terminal_discount: f64,
+    // Search root moves after the first two plies shallower unless they look best
+    root_lmr: bool,
     start_time: f64,
     time_budget_ms: f64,
     aborted: bool,
     use_adversarial: bool,
+    die_faces: u8,
+    // Player1's and Player2's configs in the last move search; the table is
+    // kept across move searches only while these stay the same
+    tt_configs: Option<(DifficultyConfig, DifficultyConfig)>,
+    tt_generation: u8,
+    // Value lookups and hits in the transposition table since the search began
+    tt_probes: u32,
+    tt_hits: u32,
+    // Per-face roll probabilities overriding the fair die, set only for the
+    // duration of a single analysis call
+    roll_weights: Option<[f64; MAX_DIE_FACES as usize]>,
+    // Seeded generator for reproducible play; None falls back to Math.random
+    rng: Option<SeededRng>,
+    // Debug builds only: recompute every same-depth TT hit and count mismatches
+    #[cfg(debug_assertions)]
+    validate_tt: bool,
+    #[cfg(debug_assertions)]
+    tt_mismatches: u32,
+    // Search events since the search began, alongside the node counts
+    counters: SearchCounters,
+}
+
+/// Counts of search events that the node counts alone don't show
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+struct SearchCounters {
+    /// Rolling states expanded into their rolls
+    chance_expansions: u32,
+    /// Late root moves kept at the reduced depth by root LMR
+    reduced_root_moves: u32,
+    /// Modeled opponent decisions taken from the table instead of searched
+    opponent_decisions_reused: u32,
 }
 
 impl SearchContext {
     fn new() -> Self {
         SearchContext {
-            tt: HashMap::with_capacity(100000),
+            tt: HashMap::with_capacity(TT_MAX_ENTRIES),
             nodes_explored: 0,
             max_nodes: 500000,
+            opponent_nodes: 0,
+            opponent_node_share: DEFAULT_OPPONENT_NODE_SHARE,
+            opponent_model_depth: 0,
+            fair_root_on_cap: false,
+            check_cap_reliability: false,
+            terminal_discount: 0.0,
+            root_lmr: false,
             start_time: 0.0,
             time_budget_ms: 0.0,
             aborted: false,
             use_adversarial: false,
+            die_faces: DEFAULT_DIE_FACES,
+            tt_configs: None,
+            tt_generation: 0,
+            tt_probes: 0,
+            tt_hits: 0,
+            roll_weights: None,
+            rng: None,
+            #[cfg(debug_assertions)]
+            validate_tt: false,
+            #[cfg(debug_assertions)]
+            tt_mismatches: 0,
+            counters: SearchCounters::default(),
         }
     }
     
     fn clear(&mut self) {
         self.tt.clear();
+        self.tt_configs = None;
         self.nodes_explored = 0;
+        self.opponent_nodes = 0;
+        self.tt_probes = 0;
+        self.tt_hits = 0;
+        self.aborted = false;
+        self.counters = SearchCounters::default();
+    }
+    
+    /// Keep the table from the previous move search when each side was searched
+    /// or modeled with the same config: Zobrist keys cover the whole position,
+    /// so entries from the last move stay valid and the overlapping tree is
+    /// found warm. Otherwise start cold. Configs are compared per side rather
+    /// than per role, since a modeled opponent's entries are what the other
+    /// side's own search finds. A nearly full table is aged by dropping entries
+    /// the previous search didn't touch, since new positions are refused once
+    /// it is full.
+    fn retain_tt_for(&mut self, player: Player, player_config: &DifficultyConfig, opponent_config: &DifficultyConfig) {
+        let configs = match player {
+            Player::Player1 => (*player_config, *opponent_config),
+            Player::Player2 => (*opponent_config, *player_config),
+        };
+        if self.tt_configs != Some(configs) {
+            self.clear();
+            self.tt_configs = Some(configs);
+            return;
+        }
+        if self.tt.len() as f64 > TT_MAX_ENTRIES as f64 * TT_AGING_FILL {
+            let previous = self.tt_generation;
+            self.tt.retain(|_, entry| entry.generation == previous);
+        }
+        self.tt_generation = self.tt_generation.wrapping_add(1);
+    }
+    
+    /// Prepare for a fixed-depth analysis search with no time budget. Analysis
+    /// runs under its own configs, so it starts from an empty table and leaves
+    /// the next move search to start cold too.
+    fn begin_fixed_search(&mut self, adversarial: bool) {
+        self.clear();
+        self.begin_search(adversarial, 0.0);
+    }
+    
+    /// Prepare for a new search: set the mode and time budget, and give it a
+    /// full node budget. Node counts used to carry over between searches, so
+    /// after a few deep searches every later one hit the cap at its first
+    /// nodes and collapsed to a static evaluation.
+    fn begin_search(&mut self, adversarial: bool, time_budget_ms: f64) {
+        self.use_adversarial = adversarial;
+        self.start_time = js_sys::Date::now();
+        self.time_budget_ms = time_budget_ms;
         self.aborted = false;
+        self.nodes_explored = 0;
+        self.opponent_nodes = 0;
+        self.tt_probes = 0;
+        self.tt_hits = 0;
+        self.counters = SearchCounters::default();
+    }
+    
+    /// Count a node visit against the active budget and report whether it is spent.
+    /// Nodes visited while modeling the opponent's own search draw on a separate
+    /// budget of `opponent_node_share * max_nodes`.
+    fn budget_exhausted(&mut self) -> bool {
+        if self.opponent_model_depth > 0 {
+            self.opponent_nodes += 1;
+            self.opponent_nodes as f64 > self.max_nodes as f64 * self.opponent_node_share
+        } else {
+            self.nodes_explored += 1;
+            self.nodes_explored > self.max_nodes
+        }
+    }
+    
+    /// Whether the running search has cut any line short, including a modeled
+    /// opponent's search running out of its share of the budget. Values and
+    /// decisions backed up since then may rest on static evaluations in place
+    /// of full subtrees.
+    fn search_cut_short(&self) -> bool {
+        self.aborted
+            || self.nodes_explored > self.max_nodes
+            || self.opponent_nodes as f64 > self.max_nodes as f64 * self.opponent_node_share
+    }
+    
+    /// Store a transposition table entry, keeping an existing entry of the same
+    /// kind that was searched deeper. New positions are dropped once the table
+    /// is full, and nothing is stored once the search has been cut short, since
+    /// the table outlives it.
+    fn tt_store(&mut self, hash: u64, entry: TTEntry) {
+        if self.search_cut_short() {
+            return;
+        }
+        let entry = TTEntry { generation: self.tt_generation, ..entry };
+        let full = self.tt.len() >= TT_MAX_ENTRIES;
+        match self.tt.get_mut(&hash) {
+            Some(existing) if existing.kind == entry.kind && existing.depth > entry.depth => {
+                existing.generation = entry.generation;
+            }
+            Some(existing) => {
+                *existing = entry;
+            }
+            None if !full => {
+                self.tt.insert(hash, entry);
+            }
+            None => {}
+        }
+    }
+    
+    /// Uniform random number in [0, 1)
+    fn random(&mut self) -> f64 {
+        match &mut self.rng {
+            Some(rng) => rng.next_f64(),
+            None => js_sys::Math::random(),
+        }
+    }
+    
+    /// Roll a uniform die with the configured number of faces
+    fn roll(&mut self) -> u8 {
+        ((self.random() * self.die_faces as f64) as u8 + 1).min(self.die_faces)
     }
     
     fn should_abort(&mut self) -> bool {
@@ -176,7 +680,7 @@ impl SearchContext {
             return false;
         }
         // Check time every 1000 nodes to avoid overhead
-        if self.nodes_explored % 1000 == 0 {
+        if (self.nodes_explored + self.opponent_nodes).is_multiple_of(1000) {
             let elapsed = js_sys::Date::now() - self.start_time;
             if elapsed >= self.time_budget_ms {
                 self.aborted = true;
@@ -187,50 +691,163 @@ impl SearchContext {
     }
 }
 
+// Zobrist hashing: one random key per (cell, die value) across both grids,
+// plus keys for the side to move and the rolled die. Generated at compile time
+// with splitmix64 so keys are stable across builds and platforms.
+const ZOBRIST_VALUES: usize = MAX_DIE_FACES as usize + 1;
+
+const fn splitmix64(seed: u64) -> u64 {
+    let mut z = seed.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+const fn build_zobrist_cells() -> [[u64; ZOBRIST_VALUES]; 18] {
+    let mut table = [[0u64; ZOBRIST_VALUES]; 18];
+    let mut cell = 0;
+    while cell < 18 {
+        // Empty cells (value 0) contribute nothing
+        let mut value = 1;
+        while value < ZOBRIST_VALUES {
+            table[cell][value] = splitmix64((cell * ZOBRIST_VALUES + value) as u64);
+            value += 1;
+        }
+        cell += 1;
+    }
+    table
+}
+
+const fn build_zobrist_dice() -> [u64; ZOBRIST_VALUES] {
+    let mut table = [0u64; ZOBRIST_VALUES];
+    let mut value = 1;
+    while value < ZOBRIST_VALUES {
+        table[value] = splitmix64((18 * ZOBRIST_VALUES + value) as u64);
+        value += 1;
+    }
+    table
+}
+
+static ZOBRIST_CELLS: [[u64; ZOBRIST_VALUES]; 18] = build_zobrist_cells();
+static ZOBRIST_DICE: [u64; ZOBRIST_VALUES] = build_zobrist_dice();
+const ZOBRIST_PLAYER2: u64 = splitmix64(19 * ZOBRIST_VALUES as u64);
+const ZOBRIST_OPPONENT_DECISION: u64 = splitmix64(20 * ZOBRIST_VALUES as u64);
+
+/// Zobrist key of the two grids and the side to move (ignores die, phase and depth)
+fn zobrist_board(state: &GameState) -> u64 {
+    let mut key = 0u64;
+    for (i, (&v1, &v2)) in state.grid1.data.iter().zip(state.grid2.data.iter()).enumerate() {
+        key ^= ZOBRIST_CELLS[i][v1 as usize] ^ ZOBRIST_CELLS[9 + i][v2 as usize];
+    }
+    if state.current_player == Player::Player2 {
+        key ^= ZOBRIST_PLAYER2;
+    }
+    key
+}
+
 // Fast hash function for game state
 fn hash_state(state: &GameState, depth: u32, is_max: bool) -> u64 {
-    let mut hash = 0u64;
-    // Hash grids
-    for i in 0..9 {
-        hash = hash.wrapping_mul(31).wrapping_add(state.grid1.data[i] as u64);
-        hash = hash.wrapping_mul(31).wrapping_add(state.grid2.data[i] as u64);
-    }
-    hash = hash.wrapping_mul(31).wrapping_add(state.current_player as u64);
-    hash = hash.wrapping_mul(31).wrapping_add(state.current_die.unwrap_or(0) as u64);
+    let mut hash = zobrist_board(state) ^ ZOBRIST_DICE[state.current_die.unwrap_or(0) as usize];
     hash = hash.wrapping_mul(31).wrapping_add(depth as u64);
     hash = hash.wrapping_mul(31).wrapping_add(if is_max { 1 } else { 0 });
     hash
 }
 
+/// Key for caching the modeled opponent's move choice, tagged so it can't
+/// collide with value entries for the same position
+fn hash_opponent_decision(state: &GameState, depth: u32) -> u64 {
+    hash_state(state, depth, false) ^ ZOBRIST_OPPONENT_DECISION
+}
+
 // Scoring functions (optimized)
 #[inline]
-fn calculate_column_score(column: &[u8; 3]) -> i32 {
-    let mut counts = [0u8; 7]; // indices 1-6 used
+fn column_value_counts(column: &[u8; 3]) -> [u8; MAX_DIE_FACES as usize + 1] {
+    let mut counts = [0u8; MAX_DIE_FACES as usize + 1]; // index 0 unused
     for &v in column.iter() {
-        if v != 0 {
+        // Cells past the largest supported die can't score; skip them rather
+        // than index past the histogram
+        if v != 0 && v <= MAX_DIE_FACES {
             counts[v as usize] += 1;
         }
     }
+    counts
+}
+
+#[inline]
+fn calculate_column_score(column: &[u8; 3]) -> i32 {
+    let counts = column_value_counts(column);
     let mut total = 0;
-    for value in 1..=6 {
-        let count = counts[value] as i32;
+    for (value, &count) in counts.iter().enumerate().skip(1) {
+        let count = count as i32;
         total += value as i32 * count * count;
     }
     total
 }
 
+/// Number of entries in the column score table (one per ordered triple of cell values)
+const COLUMN_TABLE_SIZE: usize = ZOBRIST_VALUES * ZOBRIST_VALUES * ZOBRIST_VALUES;
+
+/// Precomputed score of every possible column, indexed by `column_table_index`.
+/// Each die scores its value times the number of equal dice in the column.
+const fn build_column_score_table() -> [u16; COLUMN_TABLE_SIZE] {
+    let mut table = [0u16; COLUMN_TABLE_SIZE];
+    let mut idx = 0;
+    while idx < COLUMN_TABLE_SIZE {
+        let cells = [idx / (ZOBRIST_VALUES * ZOBRIST_VALUES), (idx / ZOBRIST_VALUES) % ZOBRIST_VALUES, idx % ZOBRIST_VALUES];
+        let mut score = 0;
+        let mut i = 0;
+        while i < 3 {
+            if cells[i] != 0 {
+                let mut matches = 0;
+                let mut j = 0;
+                while j < 3 {
+                    if cells[j] == cells[i] {
+                        matches += 1;
+                    }
+                    j += 1;
+                }
+                score += cells[i] * matches;
+            }
+            i += 1;
+        }
+        table[idx] = score as u16;
+        idx += 1;
+    }
+    table
+}
+
+static COLUMN_SCORE_TABLE: [u16; COLUMN_TABLE_SIZE] = build_column_score_table();
+
 #[inline]
-fn calculate_grid_score(grid: &Grid) -> i32 {
-    let mut total = 0;
-    for col in 0..3 {
-        let column = [
-            grid.get(col, 0),
-            grid.get(col, 1),
-            grid.get(col, 2),
-        ];
-        total += calculate_column_score(&column);
+fn column_table_index(column: [u8; 3]) -> usize {
+    (column[0] as usize * ZOBRIST_VALUES + column[1] as usize) * ZOBRIST_VALUES + column[2] as usize
+}
+
+/// Move value for ordering only: score gained plus opponent score removed, read
+/// from the column score table instead of recounting both columns
+#[inline]
+fn order_score(state: &GameState, col: usize, die_value: u8, player: Player) -> i32 {
+    let (my_grid, opp_grid) = match player {
+        Player::Player1 => (&state.grid1, &state.grid2),
+        Player::Player2 => (&state.grid2, &state.grid1),
+    };
+    
+    let mine = my_grid.column(col);
+    let mut placed = mine;
+    if let Some(slot) = placed.iter_mut().find(|v| **v == 0) {
+        *slot = die_value;
     }
-    total
+    
+    let theirs = opp_grid.column(col);
+    let captured = theirs.map(|v| if v == die_value { 0 } else { v });
+    
+    COLUMN_SCORE_TABLE[column_table_index(placed)] as i32 - COLUMN_SCORE_TABLE[column_table_index(mine)] as i32
+        + COLUMN_SCORE_TABLE[column_table_index(theirs)] as i32 - COLUMN_SCORE_TABLE[column_table_index(captured)] as i32
+}
+
+#[inline]
+fn calculate_grid_score(grid: &Grid) -> i32 {
+    (0..3).map(|col| calculate_column_score(&grid.column(col))).sum()
 }
 
 #[inline]
@@ -243,11 +860,8 @@ fn calculate_move_score_gain(grid: &Grid, col: usize, die_value: u8) -> i32 {
     let current_score = calculate_column_score(&column);
     
     let mut new_column = column;
-    for row in 0..3 {
-        if new_column[row] == 0 {
-            new_column[row] = die_value;
-            break;
-        }
+    if let Some(slot) = new_column.iter_mut().find(|v| **v == 0) {
+        *slot = die_value;
     }
     let new_score = calculate_column_score(&new_column);
     new_score - current_score
@@ -262,25 +876,56 @@ fn calculate_opponent_score_loss(opponent_grid: &Grid, col: usize, die_value: u8
     ];
     let current_score = calculate_column_score(&column);
     
-    let mut new_column = column;
-    for row in 0..3 {
-        if new_column[row] == die_value {
-            new_column[row] = 0;
-        }
-    }
-    // Compact
+    // Remove matching dice and compact
     let mut compacted = [0u8; 3];
-    let mut idx = 0;
-    for row in 0..3 {
-        if new_column[row] != 0 {
-            compacted[idx] = new_column[row];
-            idx += 1;
-        }
+    for (idx, &v) in column.iter().filter(|&&v| v != 0 && v != die_value).enumerate() {
+        compacted[idx] = v;
     }
     let new_score = calculate_column_score(&compacted);
     current_score - new_score
 }
 
+/// Dice at or above this value are worth saving for a column they can pair in
+const HIGH_DIE_MIN: u8 = 5;
+
+/// How much of a column's high dice is wasted: each high die with no partner
+/// counts its face value, scaled by how full the column is (a full column
+/// can no longer grow into a combo)
+fn column_high_die_waste(column: &[u8; 3]) -> f64 {
+    let filled = column.iter().filter(|&&v| v != 0).count() as f64;
+    column.iter()
+        .filter(|&&v| v >= HIGH_DIE_MIN && column.iter().filter(|&&w| w == v).count() == 1)
+        .map(|&v| v as f64 * filled / 3.0)
+        .sum()
+}
+
+/// Waste from placing `die_value` in `col`: zero for low dice and for a die
+/// that joins a match, otherwise the waste of the die in its new column
+fn high_die_waste(grid: &Grid, col: usize, die_value: u8) -> f64 {
+    if die_value < HIGH_DIE_MIN {
+        return 0.0;
+    }
+    let column = [grid.get(col, 0), grid.get(col, 1), grid.get(col, 2)];
+    if column.contains(&die_value) {
+        return 0.0;
+    }
+    let filled = column.iter().filter(|&&v| v != 0).count() as f64 + 1.0;
+    die_value as f64 * filled / 3.0
+}
+
+/// Defensive term of the advanced evaluation for one column: half the value of
+/// the dice in `my_col` while the opponent's matching column still has room to
+/// attack it, 0.0 once it is full
+fn vulnerability_term(my_col: &[u8; 3], opp_col: &[u8; 3]) -> f64 {
+    if !opp_col.contains(&0) {
+        return 0.0;
+    }
+    my_col.iter()
+        .filter(|&&v| v != 0)
+        .map(|&v| v as f64 * 0.5)
+        .sum()
+}
+
 // Evaluation functions
 fn evaluate_basic(state: &GameState, player: Player) -> f64 {
     let score1 = calculate_grid_score(&state.grid1) as f64;
@@ -337,13 +982,24 @@ fn evaluate_advanced(state: &GameState, player: Player, config: &DifficultyConfi
             opp_grid.get(col, 2),
         ];
         
-        let opp_empty = opp_col.iter().filter(|&&v| v == 0).count();
-        if opp_empty > 0 {
-            let vulnerability: f64 = my_col.iter()
-                .filter(|&&v| v != 0)
-                .map(|&v| v as f64 * 0.5)
-                .sum();
-            positional -= vulnerability * game_progress * config.defense_weight;
+        positional -= vulnerability_term(&my_col, &opp_col) * game_progress * config.defense_weight;
+        
+        if config.symmetric_positional {
+            // Mirror both terms: the opponent threatens our stacks and their own
+            // dice are exposed to our attacks
+            let opp_progress = opp_grid.data.iter().filter(|&&v| v != 0).count() as f64 / 9.0;
+            if !my_grid.is_column_full(col) {
+                let potential_damage = calculate_opponent_score_loss(my_grid, col, 6) as f64;
+                positional -= (potential_damage / 6.0) * (1.0 - opp_progress * 0.3) * config.defense_weight;
+            }
+            positional += vulnerability_term(&opp_col, &my_col) * opp_progress * config.offense_weight;
+        }
+        
+        if config.high_die_waste_weight > 0.0 {
+            positional -= column_high_die_waste(&my_col) * config.high_die_waste_weight;
+            if config.symmetric_positional {
+                positional += column_high_die_waste(&opp_col) * config.high_die_waste_weight;
+            }
         }
     }
     
@@ -351,28 +1007,129 @@ fn evaluate_advanced(state: &GameState, player: Player, config: &DifficultyConfi
 }
 
 fn evaluate(state: &GameState, player: Player, config: &DifficultyConfig) -> f64 {
-    if config.advanced_eval {
+    if config.solitaire {
+        let grid = if player == Player::Player1 { &state.grid1 } else { &state.grid2 };
+        return calculate_grid_score(grid) as f64;
+    }
+    if config.objective == SearchObjective::WinProbability && state.phase == GamePhase::Ended {
+        let margin = evaluate_basic(state, player);
+        return if margin > 0.0 { 1.0 } else if margin < 0.0 { 0.0 } else { 0.5 };
+    }
+    let value = if config.advanced_eval {
         evaluate_advanced(state, player, config)
     } else {
         evaluate_basic(state, player)
+    } + tempo(state, player, config);
+    match config.objective {
+        SearchObjective::ExpectedMargin => value,
+        SearchObjective::WinProbability => 1.0 / (1.0 + (-value / WIN_PROBABILITY_SCALE).exp()),
+    }
+}
+
+/// Tempo term of `evaluate`: `tempo_bonus` for `player` while the game goes on,
+/// scaled by the share of their own grid filled, so it counts for little early
+/// on. It goes to the evaluating side whoever is to move, so it doesn't flip
+/// sign with the parity of the search depth, and only that side's own
+/// placements raise it: their captures leave it as it was.
+fn tempo(state: &GameState, player: Player, config: &DifficultyConfig) -> f64 {
+    if config.tempo_bonus == 0.0 || state.phase == GamePhase::Ended {
+        return 0.0;
     }
+    let grid = if player == Player::Player1 { &state.grid1 } else { &state.grid2 };
+    let filled = grid.data.iter().filter(|&&v| v != 0).count();
+    config.tempo_bonus * filled as f64 / 9.0
+}
+
+/// `config.column_bias[col]` on the scale of the search objective. The bias is
+/// in points, so under `WinProbability` it is converted at the logistic's slope
+/// at even chances rather than added to a probability as it stands.
+fn column_bias(config: &DifficultyConfig, col: usize) -> f64 {
+    match config.objective {
+        SearchObjective::ExpectedMargin => config.column_bias[col],
+        SearchObjective::WinProbability => config.column_bias[col] / (4.0 * WIN_PROBABILITY_SCALE),
+    }
+}
+
+/// Evaluate a finished game, scaled by the depth still remaining so that with a
+/// terminal discount a sooner win scores higher and a later loss less badly
+fn evaluate_terminal(
+    state: &GameState,
+    player: Player,
+    config: &DifficultyConfig,
+    depth_remaining: u32,
+    ctx: &SearchContext,
+) -> f64 {
+    let value = evaluate(state, player, config);
+    let value = if config.objective == SearchObjective::WinProbability {
+        // A probability can't be scaled; sooner and later wins are worth the same
+        value
+    } else {
+        value * (1.0 + ctx.terminal_discount * depth_remaining as f64)
+    };
+    debug_assert!(
+        value.abs() <= value_bound(config, depth_remaining, ctx),
+        "terminal value {value} is outside the pruning bound"
+    );
+    value
 }
 
-fn evaluate_move_quick(state: &GameState, col: usize, die_value: u8, player: Player) -> f64 {
+/// `evaluate` at a search leaf, checked against the bound chance nodes prune
+/// with: a leaf outside it would make their cutoffs unsound
+fn evaluate_leaf(state: &GameState, player: Player, config: &DifficultyConfig, ctx: &SearchContext) -> f64 {
+    let value = evaluate(state, player, config);
+    debug_assert!(value.abs() <= value_bound(config, 0, ctx), "leaf value {value} is outside the pruning bound");
+    value
+}
+
+fn evaluate_move_quick(
+    state: &GameState,
+    col: usize,
+    die_value: u8,
+    player: Player,
+    config: &DifficultyConfig,
+) -> f64 {
     let (my_grid, opp_grid) = match player {
         Player::Player1 => (&state.grid1, &state.grid2),
         Player::Player2 => (&state.grid2, &state.grid1),
     };
     
-    let score_gain = calculate_move_score_gain(my_grid, col, die_value) as f64;
-    let opponent_loss = calculate_opponent_score_loss(opp_grid, col, die_value) as f64;
-    score_gain + opponent_loss
+    let mut score = calculate_move_score_gain(my_grid, col, die_value) as f64;
+    if !config.solitaire {
+        score += calculate_opponent_score_loss(opp_grid, col, die_value) as f64;
+    }
+    // Only ever a preference between legal columns; with every column poor the
+    // least wasteful still gets played
+    if config.high_die_waste_weight > 0.0 {
+        score -= high_die_waste(my_grid, col, die_value) * config.high_die_waste_weight;
+    }
+    score
+}
+
+/// The legal column with the best immediate gain plus capture for `player`
+/// (the first such column on ties), or None without a die to place
+fn greedy_column(
+    state: &GameState,
+    legal_columns: &[usize],
+    player: Player,
+    config: &DifficultyConfig,
+) -> Option<usize> {
+    let die_value = state.current_die?;
+    let mut best_col = *legal_columns.first()?;
+    let mut best_score = f64::NEG_INFINITY;
+    for &col in legal_columns {
+        let score = evaluate_move_quick(state, col, die_value, player, config);
+        if score > best_score {
+            best_score = score;
+            best_col = col;
+        }
+    }
+    Some(best_col)
 }
 
 fn order_moves(state: &GameState, columns: &[usize], player: Player) -> Vec<usize> {
     if let Some(die_value) = state.current_die {
         let mut scored: Vec<(usize, f64)> = columns.iter()
-            .map(|&col| (col, evaluate_move_quick(state, col, die_value, player)))
+            .map(|&col| (col, order_score(state, col, die_value, player) as f64))
             .collect();
         scored.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
         scored.into_iter().map(|(col, _)| col).collect()
@@ -382,6 +1139,16 @@ fn order_moves(state: &GameState, columns: &[usize], player: Player) -> Vec<usiz
 }
 
 fn apply_move(state: &GameState, col: usize) -> Option<GameState> {
+    place_current_die(state, col, true)
+}
+
+/// `apply_move` under the rules `config` searches with: solitaire skips the
+/// capture, leaving the opponent's grid untouched
+fn apply_move_for(state: &GameState, col: usize, config: &DifficultyConfig) -> Option<GameState> {
+    place_current_die(state, col, !config.solitaire)
+}
+
+fn place_current_die(state: &GameState, col: usize, captures: bool) -> Option<GameState> {
     let die_value = state.current_die?;
     let mut new_state = state.clone();
     
@@ -394,7 +1161,9 @@ fn apply_move(state: &GameState, col: usize) -> Option<GameState> {
         return None;
     }
     
-    opp_grid.remove_matching(col, die_value);
+    if captures {
+        opp_grid.remove_matching(col, die_value);
+    }
     
     // Check if game ended
     if my_grid.is_full() {
@@ -421,29 +1190,139 @@ fn roll_die(state: &GameState, die_value: u8) -> GameState {
     new_state
 }
 
-fn max_node(
+/// Inputs to the legacy i32 entry points, read as they always were before the
+/// strict `*_outcome` checks: any nonzero player code means Player2 and only the
+/// first nine cells of each grid count
+fn legacy_inputs<'a>(grid1: &'a [u8], grid2: &'a [u8], current_player: u8) -> (&'a [u8], &'a [u8], u8) {
+    (&grid1[..grid1.len().min(9)], &grid2[..grid2.len().min(9)], current_player.min(1))
+}
+
+/// Whether the die and every grid cell fit on a die with `faces` faces
+fn dice_in_range(grid1: &[u8], grid2: &[u8], current_die: u8, faces: u8) -> bool {
+    current_die <= faces && grid1.iter().chain(grid2.iter()).all(|&v| v <= faces)
+}
+
+/// Build a game state from the flat grids and player/die codes passed in from JS
+fn state_from_js(grid1: &[u8], grid2: &[u8], current_player: u8, current_die: u8) -> GameState {
+    GameState {
+        grid1: Grid::from_slice(grid1),
+        grid2: Grid::from_slice(grid2),
+        current_player: if current_player == 0 { Player::Player1 } else { Player::Player2 },
+        current_die: if current_die == 0 { None } else { Some(current_die) },
+        phase: if current_die == 0 { GamePhase::Rolling } else { GamePhase::Placing },
+        turn_number: 1,
+    }
+}
+
+/// Largest difference between a cached and a recomputed value that TT
+/// validation accepts
+#[cfg(debug_assertions)]
+const TT_VALIDATION_TOLERANCE: f64 = 1e-6;
+
+/// Recompute a transposition-table hit from scratch (fresh table, unlimited
+/// budget, same rules and settings) and log the position when the cached value
+/// disagrees. Values involving a randomized opponent model or a search that hit
+/// the node cap can differ legitimately.
+#[cfg(debug_assertions)]
+#[allow(clippy::too_many_arguments)]
+fn validate_tt_hit(
     state: &GameState,
     depth: u32,
+    is_max: bool,
+    cached: f64,
     player: Player,
     player_config: &DifficultyConfig,
     opponent_config: &DifficultyConfig,
     ctx: &mut SearchContext,
-) -> f64 {
-    ctx.nodes_explored += 1;
-    
-    if ctx.nodes_explored > ctx.max_nodes || ctx.should_abort() || state.phase == GamePhase::Ended || depth == 0 {
-        return evaluate(state, player, player_config);
-    }
+) {
+    let mut scratch = SearchContext::new();
+    scratch.max_nodes = u32::MAX;
+    scratch.opponent_node_share = ctx.opponent_node_share;
+    scratch.opponent_model_depth = ctx.opponent_model_depth;
+    scratch.terminal_discount = ctx.terminal_discount;
+    scratch.use_adversarial = ctx.use_adversarial;
+    scratch.die_faces = ctx.die_faces;
+    scratch.roll_weights = ctx.roll_weights;
+    scratch.rng = ctx.rng;
     
-    if state.phase == GamePhase::Rolling {
-        return chance_node(state, depth, player, player_config, opponent_config, ctx);
+    let fresh = if is_max {
+        max_node(state, depth, f64::NEG_INFINITY, f64::INFINITY, player, player_config, opponent_config, &mut scratch)
+    } else {
+        min_node(state, depth, f64::NEG_INFINITY, f64::INFINITY, player, player_config, opponent_config, &mut scratch)
+    };
+    if (fresh - cached).abs() > TT_VALIDATION_TOLERANCE {
+        ctx.tt_mismatches += 1;
+        web_sys::console::warn_1(&format!(
+            "TT mismatch at depth {depth} ({}): cached {cached}, recomputed {fresh}; grid1 {:?} grid2 {:?} to move {:?} die {:?}",
+            if is_max { "max" } else { "min" },
+            state.grid1.data, state.grid2.data, state.current_player, state.current_die,
+        ).into());
     }
-    
-    // Check transposition table
-    let hash = hash_state(state, depth, true);
-    if let Some(entry) = ctx.tt.get(&hash) {
-        if entry.depth >= depth {
-            return entry.value;
+}
+
+// The search alternates decision and chance nodes: max_node / min_node place the
+// rolled die (one ply, decrementing depth) and hand the resulting rolling state
+// to chance_node, the only place a roll is expanded.
+//
+// Decision nodes prune with an (alpha, beta) window and return fail-soft
+// values: a result at or below alpha is only an upper bound, one at or above
+// beta only a lower bound, and only values strictly inside the window are
+// exact and stored in the transposition table. Chance nodes can't cut on a
+// single child, so they use Star1 instead: with every value bounded by
+// `value_bound`, a roll is searched with the window that could still move the
+// average into (alpha, beta), and the node stops once the rolls left can't.
+
+/// Largest magnitude a search value can reach under `config` with `depth`
+/// plies left, so chance nodes can bound the rolls they haven't searched.
+/// Deliberately loose: a bound that is too tight would make pruning unsound.
+fn value_bound(config: &DifficultyConfig, depth: u32, ctx: &SearchContext) -> f64 {
+    let faces = ctx.die_faces as f64;
+    let max_column = calculate_column_score(&[ctx.die_faces; 3]) as f64;
+    let static_bound = if config.solitaire {
+        3.0 * max_column
+    } else if config.objective == SearchObjective::WinProbability {
+        1.0
+    } else if config.advanced_eval {
+        // Attack, exposure and high-die waste terms for both sides in every column
+        let weights = config.offense_weight.abs() + config.defense_weight.abs();
+        let attack = max_column.max(calculate_column_score(&[6; 3]) as f64) / 6.0;
+        let positional = 3.0 * 2.0 * ((attack + 1.5 * faces) * weights + 3.0 * faces * config.high_die_waste_weight.abs());
+        (3.0 * max_column * config.offense_weight.abs() + positional + config.tempo_bonus.abs()).max(10000.0)
+    } else {
+        3.0 * max_column + config.tempo_bonus.abs()
+    };
+    let max_bias = (0..3).fold(0.0f64, |m, col| m.max(column_bias(config, col).abs()));
+    static_bound * (1.0 + ctx.terminal_discount * depth as f64) + depth as f64 * max_bias
+}
+
+#[allow(clippy::too_many_arguments)]
+fn max_node(
+    state: &GameState,
+    depth: u32,
+    alpha: f64,
+    beta: f64,
+    player: Player,
+    player_config: &DifficultyConfig,
+    opponent_config: &DifficultyConfig,
+    ctx: &mut SearchContext,
+) -> f64 {
+    if ctx.budget_exhausted() || ctx.should_abort() || state.phase == GamePhase::Ended || depth == 0 {
+        return evaluate_leaf(state, player, player_config, ctx);
+    }
+    
+    debug_assert!(state.phase != GamePhase::Rolling, "rolling states belong to chance_node");
+    
+    // Check transposition table
+    let hash = hash_state(state, depth, true);
+    ctx.tt_probes += 1;
+    if let Some(&entry) = ctx.tt.get(&hash) {
+        if entry.kind == NodeKind::Value && entry.depth >= depth {
+            ctx.tt_hits += 1;
+            #[cfg(debug_assertions)]
+            if ctx.validate_tt && entry.depth == depth {
+                validate_tt_hit(state, depth, true, entry.value, player, player_config, opponent_config, ctx);
+            }
+            return entry.value;
         }
     }
     
@@ -457,51 +1336,54 @@ fn max_node(
         .collect();
     
     if legal_columns.is_empty() {
-        return evaluate(state, player, player_config);
+        return evaluate_leaf(state, player, player_config, ctx);
     }
     
     let ordered = order_moves(state, &legal_columns, player);
     let mut max_value = f64::NEG_INFINITY;
+    let mut window_low = alpha;
     
     for col in ordered {
-        if let Some(new_state) = apply_move(state, col) {
+        if let Some(new_state) = apply_move_for(state, col, player_config) {
+            let bias = column_bias(player_config, col);
             let value = if new_state.phase == GamePhase::Ended {
-                evaluate(&new_state, player, player_config)
-            } else if new_state.current_player == player {
-                chance_node(&new_state, depth - 1, player, player_config, opponent_config, ctx)
+                evaluate_terminal(&new_state, player, player_config, depth - 1, ctx)
             } else {
-                min_node(&new_state, depth - 1, player, player_config, opponent_config, ctx)
-            };
+                chance_node_window(&new_state, depth - 1, window_low - bias, beta - bias, player, player_config, opponent_config, ctx)
+            } + bias;
             
             max_value = max_value.max(value);
+            if max_value >= beta {
+                break;
+            }
+            window_low = window_low.max(max_value);
         }
     }
     
     // Store in transposition table (limit size)
-    if ctx.tt.len() < 100000 {
-        ctx.tt.insert(hash, TTEntry { depth, value: max_value });
+    if max_value > alpha && max_value < beta {
+        ctx.tt_store(hash, TTEntry::value(depth, max_value));
     }
     
     max_value
 }
 
+#[allow(clippy::too_many_arguments)]
 fn min_node(
     state: &GameState,
     depth: u32,
+    alpha: f64,
+    beta: f64,
     player: Player,
     player_config: &DifficultyConfig,
     opponent_config: &DifficultyConfig,
     ctx: &mut SearchContext,
 ) -> f64 {
-    ctx.nodes_explored += 1;
-    
-    if ctx.nodes_explored > ctx.max_nodes || ctx.should_abort() || state.phase == GamePhase::Ended || depth == 0 {
-        return evaluate(state, player, player_config);
+    if ctx.budget_exhausted() || ctx.should_abort() || state.phase == GamePhase::Ended || depth == 0 {
+        return evaluate_leaf(state, player, player_config, ctx);
     }
     
-    if state.phase == GamePhase::Rolling {
-        return chance_node(state, depth, player, player_config, opponent_config, ctx);
-    }
+    debug_assert!(state.phase != GamePhase::Rolling, "rolling states belong to chance_node");
     
     let grid = match state.current_player {
         Player::Player1 => &state.grid1,
@@ -513,15 +1395,21 @@ fn min_node(
         .collect();
     
     if legal_columns.is_empty() {
-        return evaluate(state, player, player_config);
+        return evaluate_leaf(state, player, player_config, ctx);
     }
 
     // TRUE ADVERSARIAL SEARCH: opponent plays optimally against us
     if ctx.use_adversarial {
         // Check transposition table
         let hash = hash_state(state, depth, false);
-        if let Some(entry) = ctx.tt.get(&hash) {
-            if entry.depth >= depth {
+        ctx.tt_probes += 1;
+        if let Some(&entry) = ctx.tt.get(&hash) {
+            if entry.kind == NodeKind::Value && entry.depth >= depth {
+                ctx.tt_hits += 1;
+                #[cfg(debug_assertions)]
+                if ctx.validate_tt && entry.depth == depth {
+                    validate_tt_hit(state, depth, false, entry.value, player, player_config, opponent_config, ctx);
+                }
                 return entry.value;
             }
         }
@@ -529,24 +1417,27 @@ fn min_node(
         // Order moves from opponent's perspective (best for them)
         let ordered = order_moves(state, &legal_columns, state.current_player);
         let mut min_value = f64::INFINITY;
+        let mut window_high = beta;
         
         for col in ordered {
-            if let Some(new_state) = apply_move(state, col) {
+            if let Some(new_state) = apply_move_for(state, col, player_config) {
                 let value = if new_state.phase == GamePhase::Ended {
-                    evaluate(&new_state, player, player_config)
-                } else if new_state.current_player == player {
-                    chance_node(&new_state, depth - 1, player, player_config, opponent_config, ctx)
+                    evaluate_terminal(&new_state, player, player_config, depth - 1, ctx)
                 } else {
-                    chance_node(&new_state, depth - 1, player, player_config, opponent_config, ctx)
+                    chance_node_window(&new_state, depth - 1, alpha, window_high, player, player_config, opponent_config, ctx)
                 };
                 
                 min_value = min_value.min(value);
+                if min_value <= alpha {
+                    break;
+                }
+                window_high = window_high.min(min_value);
             }
         }
         
         // Store in transposition table
-        if ctx.tt.len() < 100000 {
-            ctx.tt.insert(hash, TTEntry { depth, value: min_value });
+        if min_value > alpha && min_value < beta {
+            ctx.tt_store(hash, TTEntry::value(depth, min_value));
         }
         
         return min_value;
@@ -556,61 +1447,86 @@ fn min_node(
     let opponent = state.current_player;
     let opponent_move: Option<usize> = if opponent_config.depth == 0 {
         // Greedy opponent
-        if let Some(die_value) = state.current_die {
-            let mut best_col = legal_columns[0];
-            let mut best_score = f64::NEG_INFINITY;
-            for &col in &legal_columns {
-                let score = evaluate_move_quick(state, col, die_value, opponent);
-                if score > best_score {
-                    best_score = score;
-                    best_col = col;
-                }
-            }
-            Some(best_col)
-        } else {
-            None
-        }
-    } else if opponent_config.randomness > 0.0 && js_sys::Math::random() < opponent_config.randomness {
+        greedy_column(state, &legal_columns, opponent, opponent_config)
+    } else if opponent_config.randomness > 0.0 && ctx.random() < opponent_config.randomness {
         // Random move
-        let idx = (js_sys::Math::random() * legal_columns.len() as f64) as usize;
+        let idx = (ctx.random() * legal_columns.len() as f64) as usize;
         Some(legal_columns[idx])
+    } else if opponent_config.skill < 1.0 && ctx.random() >= opponent_config.skill {
+        // A less than fully skilled opponent sometimes settles for the greedy move
+        greedy_column(state, &legal_columns, opponent, opponent_config)
     } else {
-        // Opponent uses expectimax - find their best move
+        // Opponent uses expectimax - find their best move, reusing the decision
+        // from a transposed line when the same position was already decided
         let opponent_search_depth = opponent_config.depth.min(depth);
-        let limited_opponent_config = DifficultyConfig {
-            depth: opponent_search_depth,
-            ..*opponent_config
+        let decision_hash = hash_opponent_decision(state, opponent_search_depth);
+        let cached = ctx.tt.get(&decision_hash)
+            .filter(|entry| entry.kind == NodeKind::OpponentDecision && entry.depth == opponent_search_depth)
+            .map(|entry| (entry.best_col as usize, entry.value));
+        let decision = if cached.is_some() {
+            ctx.counters.opponent_decisions_reused += 1;
+            cached
+        } else {
+            let limited_opponent_config = DifficultyConfig {
+                depth: opponent_search_depth,
+                ..*opponent_config
+            };
+            // Both depths are at least 1 here (depth 0 returned above and a depth-0
+            // opponent took the greedy branch), but keep the child depth saturating
+            // so a future caller can never wrap it to u32::MAX
+            let opponent_child_depth = opponent_search_depth.saturating_sub(1);
+            let ordered = order_moves(state, &legal_columns, opponent);
+            let mut best_move: Option<usize> = None;
+            let mut best_value = f64::NEG_INFINITY;
+            let mut worst_value = f64::INFINITY;
+            
+            ctx.opponent_model_depth += 1;
+            for col in ordered {
+                if let Some(new_state) = apply_move_for(state, col, player_config) {
+                    let value = if new_state.phase == GamePhase::Ended {
+                        evaluate_terminal(&new_state, opponent, &limited_opponent_config, opponent_child_depth, ctx)
+                    } else {
+                        chance_node(&new_state, opponent_child_depth, opponent, &limited_opponent_config, player_config, ctx)
+                    };
+                
+                    if value > best_value {
+                        best_value = value;
+                        best_move = Some(col);
+                    }
+                    worst_value = worst_value.min(value);
+                }
+            }
+            ctx.opponent_model_depth -= 1;
+            
+            let decision = best_move.map(|col| (col, best_value - worst_value));
+            if let Some((col, spread)) = decision {
+                ctx.tt_store(decision_hash, TTEntry::opponent_decision(opponent_search_depth, col, spread));
+            }
+            decision
         };
-        let ordered = order_moves(state, &legal_columns, opponent);
-        let mut best_move: Option<usize> = None;
-        let mut best_value = f64::NEG_INFINITY;
         
-        for col in ordered {
-            if let Some(new_state) = apply_move(state, col) {
-                let value = if new_state.phase == GamePhase::Ended {
-                    evaluate(&new_state, opponent, &limited_opponent_config)
+        // A mistake profile makes the opponent slip more often the more is at stake
+        match decision {
+            Some((col, spread)) if opponent_config.blunder_per_point > 0.0 && legal_columns.len() > 1 => {
+                let chance = (spread * opponent_config.blunder_per_point).min(MAX_BLUNDER_CHANCE);
+                if ctx.random() < chance {
+                    let others: Vec<usize> = legal_columns.iter().copied().filter(|&c| c != col).collect();
+                    Some(others[((ctx.random() * others.len() as f64) as usize).min(others.len() - 1)])
                 } else {
-                    chance_node(&new_state, opponent_search_depth.saturating_sub(1), opponent, &limited_opponent_config, player_config, ctx)
-                };
-                
-                if value > best_value {
-                    best_value = value;
-                    best_move = Some(col);
+                    Some(col)
                 }
             }
+            _ => decision.map(|(col, _)| col),
         }
-        best_move
     };
     
     // Evaluate opponent's chosen move from our perspective
     if let Some(opp_col) = opponent_move {
-        if let Some(new_state) = apply_move(state, opp_col) {
+        if let Some(new_state) = apply_move_for(state, opp_col, player_config) {
             let value = if new_state.phase == GamePhase::Ended {
-                evaluate(&new_state, player, player_config)
-            } else if new_state.current_player == player {
-                chance_node(&new_state, depth - 1, player, player_config, opponent_config, ctx)
+                evaluate_terminal(&new_state, player, player_config, depth - 1, ctx)
             } else {
-                chance_node(&new_state, depth - 1, player, player_config, opponent_config, ctx)
+                chance_node_window(&new_state, depth - 1, alpha, beta, player, player_config, opponent_config, ctx)
             };
             return value;
         }
@@ -618,22 +1534,26 @@ fn min_node(
     
     // Fallback: evaluate all moves and take minimum
     let mut min_value = f64::INFINITY;
+    let mut window_high = beta;
     for col in legal_columns {
-        if let Some(new_state) = apply_move(state, col) {
+        if let Some(new_state) = apply_move_for(state, col, player_config) {
             let value = if new_state.phase == GamePhase::Ended {
-                evaluate(&new_state, player, player_config)
-            } else if new_state.current_player == player {
-                chance_node(&new_state, depth - 1, player, player_config, opponent_config, ctx)
+                evaluate_terminal(&new_state, player, player_config, depth - 1, ctx)
             } else {
-                chance_node(&new_state, depth - 1, player, player_config, opponent_config, ctx)
+                chance_node_window(&new_state, depth - 1, alpha, window_high, player, player_config, opponent_config, ctx)
             };
             min_value = min_value.min(value);
+            if min_value <= alpha {
+                break;
+            }
+            window_high = window_high.min(min_value);
         }
     }
     
     min_value
 }
 
+/// Exact expected value of a rolling state (or of a rolled or finished one)
 fn chance_node(
     state: &GameState,
     depth: u32,
@@ -642,34 +1562,94 @@ fn chance_node(
     opponent_config: &DifficultyConfig,
     ctx: &mut SearchContext,
 ) -> f64 {
-    ctx.nodes_explored += 1;
-    
-    if ctx.nodes_explored > ctx.max_nodes || ctx.should_abort() {
-        return evaluate(state, player, player_config);
-    }
-    
+    chance_node_window(state, depth, f64::NEG_INFINITY, f64::INFINITY, player, player_config, opponent_config, ctx)
+}
+
+/// `chance_node` searched only as far as needed to place its value relative to
+/// the (alpha, beta) window, with fail-soft results outside it
+#[allow(clippy::too_many_arguments)]
+fn chance_node_window(
+    state: &GameState,
+    depth: u32,
+    alpha: f64,
+    beta: f64,
+    player: Player,
+    player_config: &DifficultyConfig,
+    opponent_config: &DifficultyConfig,
+    ctx: &mut SearchContext,
+) -> f64 {
+    // Entry points may hand over a position with the die already rolled (or a
+    // finished one); pass it straight to the decision node without counting it
     if state.phase != GamePhase::Rolling {
         return if state.current_player == player {
-            max_node(state, depth, player, player_config, opponent_config, ctx)
+            max_node(state, depth, alpha, beta, player, player_config, opponent_config, ctx)
         } else {
-            min_node(state, depth, player, player_config, opponent_config, ctx)
+            min_node(state, depth, alpha, beta, player, player_config, opponent_config, ctx)
         };
     }
     
+    // At the horizon the roll can't change the static evaluation, so don't
+    // expand it
+    if ctx.budget_exhausted() || ctx.should_abort() || depth == 0 {
+        return evaluate_leaf(state, player, player_config, ctx);
+    }
+    ctx.counters.chance_expansions += 1;
+    
+    let bound = value_bound(player_config, depth, ctx);
+    let (low, high) = if bound.is_finite() { (-bound, bound) } else { (f64::NEG_INFINITY, f64::INFINITY) };
+    let faces = ctx.die_faces;
     let mut total_value = 0.0;
-    for die_value in 1..=6 {
+    // Probability of the rolls not searched yet
+    let mut remaining = 1.0;
+    for die_value in 1..=faces {
+        let weight = match ctx.roll_weights {
+            Some(weights) => weights[die_value as usize - 1],
+            None => 1.0 / faces as f64,
+        };
+        if weight <= 0.0 {
+            continue;
+        }
+        remaining = match ctx.roll_weights {
+            Some(_) => (remaining - weight).max(0.0),
+            None => (faces - die_value) as f64 / faces as f64,
+        };
+        // Window for this roll's value that could still leave the average
+        // inside (alpha, beta), whatever the remaining rolls turn out to be
+        let (child_alpha, child_beta) = if bound.is_finite() {
+            ((alpha - total_value - remaining * high) / weight, (beta - total_value - remaining * low) / weight)
+        } else {
+            (f64::NEG_INFINITY, f64::INFINITY)
+        };
         let rolled_state = roll_die(state, die_value);
         let value = if rolled_state.current_player == player {
-            max_node(&rolled_state, depth, player, player_config, opponent_config, ctx)
+            max_node(&rolled_state, depth, child_alpha, child_beta, player, player_config, opponent_config, ctx)
         } else {
-            min_node(&rolled_state, depth, player, player_config, opponent_config, ctx)
+            min_node(&rolled_state, depth, child_alpha, child_beta, player, player_config, opponent_config, ctx)
         };
-        total_value += value / 6.0;
+        total_value += value * weight;
+        if value <= child_alpha {
+            return total_value + remaining * high;
+        }
+        if value >= child_beta {
+            return total_value + remaining * low;
+        }
     }
     
     total_value
 }
 
+/// Root late-move reduction only applies under a node cap at or below this;
+/// with a larger budget the full-width root search is affordable and exact
+const ROOT_LMR_MAX_NODES: u32 = 50_000;
+
+/// Plies a late root move is reduced by. A whole move pair, so the reduced
+/// search ends on the same side to move as the full one and the two values
+/// compare without a parity bias
+const ROOT_LMR_REDUCTION: u32 = 2;
+
+/// Root values closer than this count as tied for the basic-score tie-break
+const ROOT_TIE_EPSILON: f64 = 1e-9;
+
 /// Internal expectimax search
 fn expectimax_internal(
     state: &GameState,
@@ -678,8 +1658,25 @@ fn expectimax_internal(
     opponent_config: &DifficultyConfig,
     ctx: &mut SearchContext,
 ) -> (Option<usize>, f64) {
+    let (best_move, best_value, _) = search_root(state, player, player_config, opponent_config, ctx, false);
+    (best_move, best_value)
+}
+
+/// The root search behind `expectimax_internal`: the move it picks, that move's
+/// value, and the value of each root move the pick was made from, in search
+/// order. With `exact` every root move is searched with an open window, so no
+/// value is a bound; otherwise moves that can't reach the best are only
+/// bounded, and a single legal move isn't searched at all.
+fn search_root(
+    state: &GameState,
+    player: Player,
+    player_config: &DifficultyConfig,
+    opponent_config: &DifficultyConfig,
+    ctx: &mut SearchContext,
+    exact: bool,
+) -> (Option<usize>, f64, Vec<(usize, f64)>) {
     if state.phase != GamePhase::Placing || state.current_die.is_none() {
-        return (None, 0.0);
+        return (None, 0.0, Vec::new());
     }
     
     let grid = match player {
@@ -692,37 +1689,229 @@ fn expectimax_internal(
         .collect();
     
     if legal_columns.is_empty() {
-        return (None, 0.0);
+        return (None, 0.0, Vec::new());
     }
     
-    if legal_columns.len() == 1 {
-        return (Some(legal_columns[0]), 0.0);
+    if legal_columns.len() == 1 && !exact {
+        return (Some(legal_columns[0]), 0.0, Vec::new());
     }
     
     let ordered = order_moves(state, &legal_columns, player);
     let mut best_move: Option<usize> = None;
     let mut best_value = f64::NEG_INFINITY;
     
-    for col in ordered {
+    let child_depth = player_config.depth.saturating_sub(1);
+    // Later moves only need exact values when they could beat, tie or land in
+    // the equal-move window of the best so far; below that a bound will do
+    let root_margin = player_config.equal_move_epsilon.max(0.0) + ROOT_TIE_EPSILON;
+    let mut best_basic = f64::NEG_INFINITY;
+    // Searched root moves, kept only for the equal-move split
+    let mut searched: Vec<(usize, f64, GameState)> = Vec::new();
+    let mut values = Vec::with_capacity(legal_columns.len());
+    let reduce_late = ctx.root_lmr && ctx.max_nodes <= ROOT_LMR_MAX_NODES;
+    for (idx, col) in ordered.into_iter().enumerate() {
         if ctx.should_abort() {
             break;
         }
         
-        if let Some(new_state) = apply_move(state, col) {
+        if let Some(new_state) = apply_move_for(state, col, player_config) {
+            let alpha = if exact { f64::NEG_INFINITY } else { best_value - root_margin };
             let value = if new_state.phase == GamePhase::Ended {
-                evaluate(&new_state, player, player_config)
+                evaluate_terminal(&new_state, player, player_config, child_depth, ctx)
+            } else if reduce_late && idx > 0 && child_depth > ROOT_LMR_REDUCTION {
+                // Late root move: search it reduced, and only pay for the full
+                // depth if it looks like it could beat the best move so far
+                let reduced = chance_node_window(&new_state, child_depth - ROOT_LMR_REDUCTION, alpha, f64::INFINITY, player, player_config, opponent_config, ctx);
+                if reduced > best_value {
+                    chance_node_window(&new_state, child_depth, alpha, f64::INFINITY, player, player_config, opponent_config, ctx)
+                } else {
+                    ctx.counters.reduced_root_moves += 1;
+                    reduced
+                }
             } else {
-                chance_node(&new_state, player_config.depth.saturating_sub(1), player, player_config, opponent_config, ctx)
+                chance_node_window(&new_state, child_depth, alpha, f64::INFINITY, player, player_config, opponent_config, ctx)
             };
             
-            if value > best_value {
+            let basic = evaluate_basic(&new_state, player);
+            let better = if player_config.basic_tiebreak && (value - best_value).abs() < ROOT_TIE_EPSILON {
+                basic > best_basic
+            } else {
+                value > best_value
+            };
+            if better {
                 best_value = value;
+                best_basic = basic;
                 best_move = Some(col);
             }
+            values.push((col, value));
+            if player_config.equal_move_epsilon > 0.0 {
+                searched.push((col, value, new_state));
+            }
         }
     }
     
-    (best_move, best_value)
+    // Hitting the node cap leaves later root moves with only a static eval while
+    // earlier ones got a full search; compare them at an equal depth instead
+    if ctx.fair_root_on_cap && !ctx.aborted && ctx.nodes_explored > ctx.max_nodes {
+        let values = fair_root_values(state, player, player_config, opponent_config, ctx);
+        let (best_move, best_value) = best_root_value(&values);
+        return (best_move, best_value, values);
+    }
+    
+    if let Some((col, value)) = split_equal_moves(&searched, best_value, player, player_config, ctx) {
+        return (Some(col), value, values);
+    }
+    
+    (best_move, best_value, values)
+}
+
+/// Among root moves within `equal_move_epsilon` of the best value, pick one by
+/// the secondary criterion: at random with `equal_move_random`, otherwise the
+/// one leaving the least exposure to the opponent's next roll (earliest in move
+/// order on ties), comparing the largest capture any roll allows first with
+/// `safe_play`. None when fewer than two moves are that close.
+fn split_equal_moves(
+    searched: &[(usize, f64, GameState)],
+    best_value: f64,
+    player: Player,
+    player_config: &DifficultyConfig,
+    ctx: &mut SearchContext,
+) -> Option<(usize, f64)> {
+    let equal: Vec<&(usize, f64, GameState)> = searched.iter()
+        .filter(|&&(_, value, _)| value >= best_value - player_config.equal_move_epsilon)
+        .collect();
+    if equal.len() < 2 {
+        return None;
+    }
+    
+    let chosen = if player_config.equal_move_random {
+        equal[((ctx.random() * equal.len() as f64) as usize).min(equal.len() - 1)]
+    } else {
+        let faces = ctx.die_faces;
+        let risk = |state: &GameState| {
+            let worst = if player_config.safe_play { max_capture(state, player, faces) } else { 0 };
+            (worst, capture_exposure(state, player, faces))
+        };
+        let mut lowest = equal[0];
+        let mut lowest_risk = risk(&lowest.2);
+        for &candidate in &equal[1..] {
+            let candidate_risk = risk(&candidate.2);
+            if candidate_risk < lowest_risk {
+                lowest = candidate;
+                lowest_risk = candidate_risk;
+            }
+        }
+        lowest
+    };
+    Some((chosen.0, chosen.1))
+}
+
+/// Most points any single roll lets the opponent knock off `player`'s grid
+fn max_capture(state: &GameState, player: Player, faces: u8) -> i32 {
+    let (my_grid, opp_grid) = match player {
+        Player::Player1 => (&state.grid1, &state.grid2),
+        Player::Player2 => (&state.grid2, &state.grid1),
+    };
+    (1..=faces)
+        .flat_map(|die_value| {
+            (0..3)
+                .filter(|&col| !opp_grid.is_column_full(col))
+                .map(move |col| calculate_opponent_score_loss(my_grid, col, die_value))
+        })
+        .max()
+        .unwrap_or(0)
+}
+
+/// Points the opponent is expected to knock off `player`'s grid with their next
+/// roll, if they always take the biggest capture available
+fn capture_exposure(state: &GameState, player: Player, faces: u8) -> f64 {
+    let (my_grid, opp_grid) = match player {
+        Player::Player1 => (&state.grid1, &state.grid2),
+        Player::Player2 => (&state.grid2, &state.grid1),
+    };
+    let total: i32 = (1..=faces)
+        .map(|die_value| {
+            (0..3)
+                .filter(|&col| !opp_grid.is_column_full(col))
+                .map(|col| calculate_opponent_score_loss(my_grid, col, die_value))
+                .max()
+                .unwrap_or(0)
+        })
+        .sum();
+    total as f64 / faces as f64
+}
+
+/// Re-search every root move at a uniform reduced depth after the node cap cut
+/// a full-depth search short. The depth is halved until a pass completes within
+/// the node budget (or reaches depth 1), and each pass starts from a fresh
+/// budget and an empty transposition table, since entries written while capped
+/// may hold truncated values. Ties go to the lowest column, so the result does
+/// not depend on move ordering.
+fn fair_root_after_cap(
+    state: &GameState,
+    player: Player,
+    player_config: &DifficultyConfig,
+    opponent_config: &DifficultyConfig,
+    ctx: &mut SearchContext,
+) -> (Option<usize>, f64) {
+    best_root_value(&fair_root_values(state, player, player_config, opponent_config, ctx))
+}
+
+/// The first root move with the highest value, from values in column order
+fn best_root_value(values: &[(usize, f64)]) -> (Option<usize>, f64) {
+    let mut best: (Option<usize>, f64) = (None, f64::NEG_INFINITY);
+    for &(col, value) in values {
+        if value > best.1 {
+            best = (Some(col), value);
+        }
+    }
+    best
+}
+
+/// Each root move's value from the uniform-depth pass `fair_root_after_cap`
+/// picks from, in column order
+fn fair_root_values(
+    state: &GameState,
+    player: Player,
+    player_config: &DifficultyConfig,
+    opponent_config: &DifficultyConfig,
+    ctx: &mut SearchContext,
+) -> Vec<(usize, f64)> {
+    let mut depth = player_config.depth;
+    loop {
+        depth = (depth / 2).max(1);
+        ctx.tt.clear();
+        ctx.nodes_explored = 0;
+        ctx.opponent_nodes = 0;
+        
+        let config = DifficultyConfig { depth, ..*player_config };
+        let values = evaluate_root_moves(state, player, &config, opponent_config, ctx);
+        if ctx.nodes_explored <= ctx.max_nodes || depth == 1 {
+            return values;
+        }
+    }
+}
+
+/// Backed-up expectimax value of every legal root move, in column order
+fn evaluate_root_moves(
+    state: &GameState,
+    player: Player,
+    player_config: &DifficultyConfig,
+    opponent_config: &DifficultyConfig,
+    ctx: &mut SearchContext,
+) -> Vec<(usize, f64)> {
+    let mut values = Vec::with_capacity(3);
+    for col in get_legal_columns(state) {
+        if let Some(new_state) = apply_move_for(state, col, player_config) {
+            let value = if new_state.phase == GamePhase::Ended {
+                evaluate_terminal(&new_state, player, player_config, player_config.depth.saturating_sub(1), ctx)
+            } else {
+                chance_node(&new_state, player_config.depth.saturating_sub(1), player, player_config, opponent_config, ctx)
+            };
+            values.push((col, value));
+        }
+    }
+    values
 }
 
 /// Iterative deepening search with time budget
@@ -775,1443 +1964,6116 @@ fn iterative_deepening(
     (best_move, best_value, depth_reached)
 }
 
-// WASM bindings
+/// Pick a move for the player to move in a placing-phase state with at least one
+/// legal column: a random move with probability `randomness`, the greedy move
+/// when the search depth is 0, and otherwise a full search.
+fn choose_move(
+    state: &GameState,
+    player_config: &DifficultyConfig,
+    opponent_config: &DifficultyConfig,
+    ctx: &mut SearchContext,
+) -> (usize, SearchStats) {
+    let player = state.current_player;
+    let legal_columns = get_legal_columns(state);
+    let mut stats = SearchStats { value: f64::NAN, ..SearchStats::default() };
+    
+    if legal_columns.len() == 1 {
+        return (legal_columns[0], stats);
+    }
+    
+    // Random move based on difficulty
+    if player_config.randomness > 0.0 && ctx.random() < player_config.randomness {
+        let idx = (ctx.random() * legal_columns.len() as f64) as usize;
+        return (legal_columns[idx], stats);
+    }
+    
+    // Greedy (depth 0)
+    if player_config.search_depth() == 0 {
+        stats.greedy = true;
+        return (greedy_column(state, &legal_columns, player, player_config).unwrap_or(legal_columns[0]), stats);
+    }
+    
+    let player_config = DifficultyConfig {
+        depth: player_config.search_depth(),
+        ..*player_config
+    };
+    
+    ctx.retain_tt_for(player, &player_config, opponent_config);
+    ctx.begin_search(player_config.adversarial, player_config.time_budget_ms);
+    let started = ctx.start_time;
+    
+    // Use iterative deepening if time budget is set
+    let (best_move, best_value, depth_reached) = if player_config.time_budget_ms > 0.0 {
+        iterative_deepening(state, player, &player_config, opponent_config, ctx)
+    } else {
+        let (move_opt, value) = expectimax_internal(state, player, &player_config, opponent_config, ctx);
+        (move_opt, value, player_config.depth)
+    };
+    if best_move.is_some() {
+        stats.value = best_value;
+    }
+    // Out of time before even depth 1 finished: the one-ply pick beats a blind one
+    let best_move = best_move.or_else(|| {
+        stats.greedy = true;
+        greedy_column(state, &legal_columns, player, &player_config)
+    });
+    
+    stats.nodes = ctx.nodes_explored;
+    stats.depth_reached = depth_reached;
+    stats.tt_hits = ctx.tt_hits;
+    stats.tt_probes = ctx.tt_probes;
+    stats.truncated = ctx.aborted || ctx.nodes_explored > ctx.max_nodes;
+    if depth_reached > 0 && stats.nodes > 0 {
+        stats.branching_factor = (stats.nodes as f64).powf(1.0 / depth_reached as f64);
+    }
+    
+    if ctx.check_cap_reliability && !ctx.aborted && ctx.nodes_explored > ctx.max_nodes {
+        let (shallow_move, _) = fair_root_after_cap(state, player, &player_config, opponent_config, ctx);
+        stats.cap_disagreement = shallow_move.is_some() && shallow_move != best_move;
+    }
+    stats.elapsed_ms = js_sys::Date::now() - started;
+    
+    (best_move.unwrap_or(legal_columns[0]), stats)
+}
+
+/// Why a move request did or did not produce a column
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 #[wasm_bindgen]
-pub struct AIEngine {
-    ctx: SearchContext,
+pub enum MoveStatus {
+    /// A column was chosen
+    Ok,
+    /// A grid is already full
+    GameEnded,
+    /// The player to move has no open column. A full grid ends the game, so
+    /// positions reach `GameEnded` first; kept so callers can branch on it.
+    NoLegalMoves,
+    /// Bad player code, oversized grid, or a die out of range
+    InvalidInput,
+    /// No die to place (`current_die` is 0)
+    NotPlacing,
 }
 
+/// Result of a move request: a status plus the chosen column (-1 unless Ok)
+#[derive(Clone, Copy, Debug)]
 #[wasm_bindgen]
-impl AIEngine {
-    #[wasm_bindgen(constructor)]
-    pub fn new() -> Self {
-        AIEngine {
-            ctx: SearchContext::new(),
-        }
+pub struct MoveOutcome {
+    status: MoveStatus,
+    column: i32,
+}
+
+impl MoveOutcome {
+    fn ok(col: usize) -> Self {
+        MoveOutcome { status: MoveStatus::Ok, column: col as i32 }
     }
     
+    fn failed(status: MoveStatus) -> Self {
+        MoveOutcome { status, column: -1 }
+    }
+}
+
+#[wasm_bindgen]
+impl MoveOutcome {
+    /// Get the outcome status
     #[wasm_bindgen]
-    pub fn clear_cache(&mut self) {
-        self.ctx.clear();
+    pub fn get_status(&self) -> MoveStatus {
+        self.status
     }
     
+    /// Get the chosen column, or -1 if no move was produced
     #[wasm_bindgen]
-    pub fn get_best_move(
-        &mut self,
-        grid1: &[u8],
-        grid2: &[u8],
-        current_player: u8,
-        current_die: u8,
-        depth: u32,
-        randomness: f64,
-        offense_weight: f64,
-        defense_weight: f64,
-        advanced_eval: bool,
-        opponent_depth: u32,
-        opponent_randomness: f64,
-        opponent_offense_weight: f64,
-        opponent_defense_weight: f64,
-        opponent_advanced_eval: bool,
-    ) -> i32 {
-        // Call the extended version with default adversarial=false and time_budget=0
-        self.get_best_move_extended(
-            grid1, grid2, current_player, current_die,
-            depth, randomness, offense_weight, defense_weight, advanced_eval,
-            false, 0.0,  // adversarial, time_budget_ms
-            opponent_depth, opponent_randomness, opponent_offense_weight, opponent_defense_weight, opponent_advanced_eval,
-            false, 0.0,  // opponent adversarial, time_budget_ms
-        )
+    pub fn get_column(&self) -> i32 {
+        self.column
     }
     
+    /// Whether a column was produced
     #[wasm_bindgen]
-    pub fn get_best_move_extended(
-        &mut self,
-        grid1: &[u8],
-        grid2: &[u8],
-        current_player: u8,
-        current_die: u8,
-        depth: u32,
-        randomness: f64,
-        offense_weight: f64,
-        defense_weight: f64,
-        advanced_eval: bool,
-        adversarial: bool,
-        time_budget_ms: f64,
-        opponent_depth: u32,
-        opponent_randomness: f64,
-        opponent_offense_weight: f64,
-        opponent_defense_weight: f64,
-        opponent_advanced_eval: bool,
-        opponent_adversarial: bool,
-        opponent_time_budget_ms: f64,
-    ) -> i32 {
-        // Convert from JS arrays to GameState
-        let mut state = GameState {
-            grid1: Grid { data: [0; 9] },
-            grid2: Grid { data: [0; 9] },
-            current_player: if current_player == 0 { Player::Player1 } else { Player::Player2 },
-            current_die: if current_die == 0 { None } else { Some(current_die) },
-            phase: if current_die == 0 { GamePhase::Rolling } else { GamePhase::Placing },
-            turn_number: 1,
-        };
-        
-        // Copy grid data
-        let len1 = grid1.len().min(9);
-        for i in 0..len1 {
-            state.grid1.data[i] = grid1[i];
-        }
-        for i in len1..9 {
-            state.grid1.data[i] = 0;
+    pub fn is_ok(&self) -> bool {
+        self.status == MoveStatus::Ok
+    }
+}
+
+/// A chosen move with the value the search backed up for it
+#[derive(Clone, Copy, Debug)]
+#[wasm_bindgen]
+pub struct MoveEval {
+    column: i32,
+    value: f64,
+    nodes: u32,
+}
+
+impl MoveEval {
+    fn none() -> Self {
+        MoveEval { column: -1, value: f64::NAN, nodes: 0 }
+    }
+    
+    fn from_stats(column: i32, stats: &SearchStats) -> Self {
+        MoveEval { column, value: stats.value, nodes: stats.nodes }
+    }
+}
+
+#[wasm_bindgen]
+impl MoveEval {
+    /// Get the chosen column, or -1 if no move was produced
+    #[wasm_bindgen]
+    pub fn get_column(&self) -> i32 {
+        self.column
+    }
+    
+    /// Get the move's value from the mover's perspective: the same value that
+    /// selected it. NaN when no search picked the move (no legal move, a
+    /// forced or random move, or greedy play).
+    #[wasm_bindgen]
+    pub fn get_value(&self) -> f64 {
+        self.value
+    }
+    
+    /// Get the number of nodes the search explored
+    #[wasm_bindgen]
+    pub fn get_nodes(&self) -> u32 {
+        self.nodes
+    }
+}
+
+/// Validate a position passed in from JS and build the placing-phase state to search
+fn placing_state_from_js(
+    grid1: &[u8],
+    grid2: &[u8],
+    current_player: u8,
+    current_die: u8,
+    die_faces: u8,
+) -> Result<GameState, MoveStatus> {
+    if current_player > 1 || grid1.len() > 9 || grid2.len() > 9
+        || !dice_in_range(grid1, grid2, current_die, die_faces)
+    {
+        return Err(MoveStatus::InvalidInput);
+    }
+    
+    let state = state_from_js(grid1, grid2, current_player, current_die);
+    if state.grid1.is_full() || state.grid2.is_full() {
+        return Err(MoveStatus::GameEnded);
+    }
+    if state.phase != GamePhase::Placing {
+        return Err(MoveStatus::NotPlacing);
+    }
+    if get_legal_columns(&state).is_empty() {
+        return Err(MoveStatus::NoLegalMoves);
+    }
+    Ok(state)
+}
+
+// WASM bindings
+/// Summary of the most recent move search, for tuning and diagnostics
+#[derive(Clone, Copy, Debug, Default)]
+#[wasm_bindgen]
+pub struct SearchStats {
+    nodes: u32,
+    depth_reached: u32,
+    greedy: bool,
+    branching_factor: f64,
+    // The search hit the node cap and a complete shallower search preferred
+    // another move
+    cap_disagreement: bool,
+    tt_hits: u32,
+    tt_probes: u32,
+    truncated: bool,
+    elapsed_ms: f64,
+    // Backed-up value of the chosen move; NaN when no search chose it
+    value: f64,
+}
+
+#[wasm_bindgen]
+impl SearchStats {
+    /// Nodes explored by the search
+    #[wasm_bindgen]
+    pub fn get_nodes(&self) -> u32 {
+        self.nodes
+    }
+    
+    /// Deepest fully completed search depth (0 for greedy or random moves)
+    #[wasm_bindgen]
+    pub fn get_depth_reached(&self) -> u32 {
+        self.depth_reached
+    }
+    
+    /// Whether the move came from the greedy one-ply path rather than a search
+    #[wasm_bindgen]
+    pub fn is_greedy(&self) -> bool {
+        self.greedy
+    }
+    
+    /// Effective branching factor, `nodes^(1/depth_reached)` (0 without a search).
+    /// Every ply counts a decision node and a chance node, so values well above 3
+    /// mean the chance nodes are inflating the tree.
+    #[wasm_bindgen]
+    pub fn get_branching_factor(&self) -> f64 {
+        self.branching_factor
+    }
+    
+    /// Transposition table lookups that returned a usable value
+    #[wasm_bindgen]
+    pub fn get_tt_hits(&self) -> u32 {
+        self.tt_hits
+    }
+    
+    /// Transposition table lookups made by the search
+    #[wasm_bindgen]
+    pub fn get_tt_probes(&self) -> u32 {
+        self.tt_probes
+    }
+    
+    /// Whether the search was cut short by its time budget or node cap
+    #[wasm_bindgen]
+    pub fn is_truncated(&self) -> bool {
+        self.truncated
+    }
+    
+    /// Wall-clock time the search took in milliseconds (0 without a search)
+    #[wasm_bindgen]
+    pub fn get_elapsed_ms(&self) -> f64 {
+        self.elapsed_ms
+    }
+}
+
+/// Default largest expected margin, either way, at which a draw is worth taking
+const DEFAULT_DRAW_EPSILON: f64 = 1.0;
+
+/// Default smallest spread of outcomes over the next roll that makes playing
+/// on a real gamble
+const DEFAULT_DRAW_RISK_TOLERANCE: f64 = 2.0;
+
+#[wasm_bindgen]
+pub struct AIEngine {
+    ctx: SearchContext,
+    // Engine-wide settings (min depth, evaluation flags) layered under the
+    // per-call parameters of the player's config
+    base_config: DifficultyConfig,
+    // The same for the opponent model
+    opponent_base_config: DifficultyConfig,
+    last_stats: SearchStats,
+    // Thresholds for `should_accept_draw`, in points
+    draw_epsilon: f64,
+    draw_risk_tolerance: f64,
+    // Occurrences of each position recorded with `record_position`, keyed by
+    // Zobrist board hash, and the hash recorded last
+    position_history: HashMap<u64, u32>,
+    last_position: Option<u64>,
+}
+
+impl Default for AIEngine {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[wasm_bindgen]
+impl AIEngine {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> Self {
+        AIEngine {
+            ctx: SearchContext::new(),
+            base_config: DifficultyConfig::default(),
+            opponent_base_config: DifficultyConfig::default(),
+            last_stats: SearchStats::default(),
+            draw_epsilon: DEFAULT_DRAW_EPSILON,
+            draw_risk_tolerance: DEFAULT_DRAW_RISK_TOLERANCE,
+            position_history: HashMap::new(),
+            last_position: None,
+        }
+    }
+    
+    #[wasm_bindgen]
+    pub fn clear_cache(&mut self) {
+        self.ctx.clear();
+    }
+    
+    /// Set the number of faces on the die (6 for standard rules, 8/10/12 for variants).
+    /// Returns false and keeps the current setting if `faces` is outside 2..=12.
+    #[wasm_bindgen]
+    pub fn set_die_faces(&mut self, faces: u8) -> bool {
+        if !(2..=MAX_DIE_FACES).contains(&faces) {
+            return false;
+        }
+        if faces != self.ctx.die_faces {
+            // Cached values were backed up under a different roll distribution
+            self.ctx.clear();
+            self.ctx.die_faces = faces;
+        }
+        true
+    }
+    
+    /// Set the fraction of the node budget the opponent model may spend on its own
+    /// expectimax (clamped to 0.0-1.0). Lower values keep more of the budget for
+    /// the player's search at the cost of a less accurate opponent model.
+    #[wasm_bindgen]
+    pub fn set_opponent_node_share(&mut self, share: f64) {
+        self.ctx.opponent_node_share = if share.is_finite() { share.clamp(0.0, 1.0) } else { DEFAULT_OPPONENT_NODE_SHARE };
+    }
+    
+    /// When enabled, a search that hits the node cap re-evaluates every root move
+    /// at a uniform reduced depth instead of comparing fully searched moves with
+    /// ones that only got a static evaluation. Off by default.
+    #[wasm_bindgen]
+    pub fn set_fair_root_on_cap(&mut self, enabled: bool) {
+        self.ctx.fair_root_on_cap = enabled;
+    }
+    
+    /// When enabled, a search that hits the node cap is followed by a complete
+    /// search of the root moves at a reduced depth, and `cap_reliability`
+    /// reports whether the two agree. Costs up to one extra search per capped
+    /// move. Off by default.
+    #[wasm_bindgen]
+    pub fn set_check_cap_reliability(&mut self, enabled: bool) {
+        self.ctx.check_cap_reliability = enabled;
+    }
+    
+    /// Enable late-move reduction at the root: the first root move in the move
+    /// ordering is searched at full depth and the others two plies shallower, with
+    /// a full-depth re-search only for a move whose reduced value beats the best
+    /// so far. This trades exactness for spending a small node budget on the
+    /// most promising lines, so it relies on good move ordering. It only takes
+    /// effect when the node cap is 50,000 or less: with a larger budget the
+    /// full-width root is affordable and stays exact. Off by default.
+    #[wasm_bindgen]
+    pub fn set_root_lmr(&mut self, enabled: bool) {
+        self.ctx.root_lmr = enabled;
+    }
+    
+    /// Cap the nodes a move search may explore before it falls back to static
+    /// evaluation (500,000 by default). A small cap suits weak devices and is
+    /// where `set_root_lmr` applies.
+    #[wasm_bindgen]
+    pub fn set_max_nodes(&mut self, max_nodes: u32) {
+        self.ctx.max_nodes = max_nodes;
+    }
+    
+    /// Scale terminal values by the plies left in the search, so a win reached
+    /// sooner scores higher and a loss pushed further out scores less badly.
+    /// A small value such as 0.01 is enough to make the engine close out won
+    /// games promptly; 0 (the default) keeps terminal values flat.
+    #[wasm_bindgen]
+    pub fn set_terminal_discount(&mut self, discount: f64) {
+        let discount = if discount.is_finite() { discount.max(0.0) } else { 0.0 };
+        if discount != self.ctx.terminal_discount {
+            self.ctx.terminal_discount = discount;
+            self.ctx.clear();
+        }
+    }
+    
+    /// Set a floor on the search depth of `get_best_move` and friends, so even
+    /// a depth-0 (greedy) difficulty does some lookahead. 0 (the default) keeps
+    /// the greedy path.
+    #[wasm_bindgen]
+    pub fn set_min_depth(&mut self, min_depth: u32) {
+        self.base_config.min_depth = min_depth;
+    }
+    
+    /// Seed the engine's random choices so games can be reproduced exactly
+    #[wasm_bindgen]
+    pub fn set_seed(&mut self, seed: u64) {
+        self.ctx.rng = Some(SeededRng::new(seed));
+    }
+    
+    /// Return to unseeded randomness (Math.random)
+    #[wasm_bindgen]
+    pub fn clear_seed(&mut self) {
+        self.ctx.rng = None;
+    }
+    
+    /// Roll the die (1 to the configured number of faces) from the engine's
+    /// generator. With a seed set, a whole game is reproducible from that seed
+    /// as long as the frontend takes every roll from here rather than
+    /// Math.random, and makes the same calls in the same order, since the AI's
+    /// own random choices draw from the same stream. Unseeded, this falls back
+    /// to Math.random.
+    #[wasm_bindgen]
+    pub fn next_die(&mut self) -> u8 {
+        self.ctx.roll()
+    }
+    
+    /// When two root moves have the same backed-up value, prefer the one with the
+    /// higher raw score margin after the move, so positional terms never cost
+    /// real points on a tie. Node values deeper in the tree are unaffected.
+    /// Off by default.
+    #[wasm_bindgen]
+    pub fn set_basic_tiebreak(&mut self, enabled: bool) {
+        self.base_config.basic_tiebreak = enabled;
+    }
+    
+    /// Practice mode with no opponent interaction: placements don't capture and
+    /// the searches maximize the player's own grid score. Applies to both the
+    /// player and the opponent model. Off by default.
+    #[wasm_bindgen]
+    pub fn set_solitaire(&mut self, enabled: bool) {
+        if enabled != self.base_config.solitaire {
+            // Cached values were backed up under the other rules
+            self.ctx.clear();
+            self.base_config.solitaire = enabled;
+            self.opponent_base_config.solitaire = enabled;
+        }
+    }
+    
+    /// Treat root moves within `epsilon` of the best value as equally good and
+    /// choose among them by a secondary criterion instead of float noise: the
+    /// move leaving the smallest expected capture for the opponent's next roll,
+    /// or a random one with `random`. Negative and non-finite values are
+    /// treated as 0.0 (off, the default).
+    #[wasm_bindgen]
+    pub fn set_equal_move_epsilon(&mut self, epsilon: f64, random: bool) {
+        self.base_config.equal_move_epsilon = if epsilon.is_finite() { epsilon.max(0.0) } else { 0.0 };
+        self.base_config.equal_move_random = random;
+    }
+    
+    /// Credit the searching side with up to `bonus` points in the evaluation
+    /// while the game goes on, growing as their grid fills, to separate
+    /// otherwise balanced positions. Non-finite values are treated as 0.0 (off,
+    /// the default).
+    #[wasm_bindgen]
+    pub fn set_tempo_bonus(&mut self, bonus: f64) {
+        let bonus = if bonus.is_finite() { bonus } else { 0.0 };
+        if bonus != self.base_config.tempo_bonus {
+            // Cached values were computed without this term
+            self.ctx.clear();
+            self.base_config.tempo_bonus = bonus;
+        }
+    }
+    
+    /// Choose what the search maximizes: the expected score margin (the
+    /// default) or the probability of finishing ahead, which can prefer a
+    /// safer line late in a close game. Under `WinProbability`, move values
+    /// are probabilities, so point-based windows such as the equal-move
+    /// epsilon should be set on that scale.
+    #[wasm_bindgen]
+    pub fn set_objective(&mut self, objective: SearchObjective) {
+        if objective != self.base_config.objective {
+            self.ctx.clear();
+            self.base_config.objective = objective;
+        }
+    }
+    
+    /// Safety-first play: among root moves within `epsilon` of the best value,
+    /// take the one that leaves the opponent the smallest worst-case capture
+    /// on their next roll. This shares the equal-move window with
+    /// `set_equal_move_epsilon`, so enabling it sets that epsilon, and turns
+    /// off the random split. Disabling leaves the window as it is.
+    #[wasm_bindgen]
+    pub fn set_safe_play(&mut self, enabled: bool, epsilon: f64) {
+        self.base_config.safe_play = enabled;
+        if enabled {
+            self.base_config.equal_move_epsilon = if epsilon.is_finite() { epsilon.max(0.0) } else { 0.0 };
+            self.base_config.equal_move_random = false;
+        }
+    }
+    
+    /// Penalize spending a 5 or 6 where it pairs with nothing, more so the
+    /// fuller the column, so the AI holds high dice for columns they can stack
+    /// in. Applies to greedy play and the advanced evaluation. Negative and
+    /// non-finite weights are treated as 0.0 (off, the default).
+    #[wasm_bindgen]
+    pub fn set_high_die_waste_weight(&mut self, weight: f64) {
+        let weight = if weight.is_finite() { weight.max(0.0) } else { 0.0 };
+        if weight != self.base_config.high_die_waste_weight {
+            self.base_config.high_die_waste_weight = weight;
+            self.ctx.clear();
+        }
+    }
+    
+    /// Give the modeled opponent a human-like mistake profile: at each searched
+    /// decision they play a random other column with probability
+    /// `per_point` times the gap between their best and worst move, capped at
+    /// 0.5, so they slip most when the stakes are highest. Negative and
+    /// non-finite values are treated as 0.0 (off, the default).
+    #[wasm_bindgen]
+    pub fn set_opponent_blunder_rate(&mut self, per_point: f64) {
+        let per_point = if per_point.is_finite() { per_point.max(0.0) } else { 0.0 };
+        if per_point != self.opponent_base_config.blunder_per_point {
+            self.opponent_base_config.blunder_per_point = per_point;
+            self.ctx.clear();
+        }
+    }
+    
+    /// Model a weaker opponent as a blend of greedy and searched play: at each
+    /// modeled decision they search with probability `skill` and otherwise
+    /// take the greedy move. Clamped to 0..=1; 1.0 (the default) always searches.
+    #[wasm_bindgen]
+    pub fn set_opponent_skill(&mut self, skill: f64) {
+        let skill = if skill.is_finite() { skill.clamp(0.0, 1.0) } else { 1.0 };
+        if skill != self.opponent_base_config.skill {
+            self.opponent_base_config.skill = skill;
+            self.ctx.clear();
+        }
+    }
+    
+    /// Use the symmetric positional evaluation for the player's searches, which
+    /// also weighs the opponent's attack potential against us and the exposure
+    /// of their own dice. Off by default.
+    #[wasm_bindgen]
+    pub fn set_symmetric_eval(&mut self, enabled: bool) {
+        if enabled != self.base_config.symmetric_positional {
+            // Cached values were computed with the other evaluation
+            self.ctx.clear();
+            self.base_config.symmetric_positional = enabled;
+        }
+    }
+    
+    /// Debug builds only: on every transposition-table hit at the cached depth,
+    /// recompute the value from scratch and log the position to the console if
+    /// it differs. Very slow; for hardening the caching logic. Off by default.
+    #[cfg(debug_assertions)]
+    #[wasm_bindgen]
+    pub fn set_tt_validation(&mut self, enabled: bool) {
+        self.ctx.validate_tt = enabled;
+    }
+    
+    /// Debug builds only: TT hits that failed validation since the engine was
+    /// created
+    #[cfg(debug_assertions)]
+    #[wasm_bindgen]
+    pub fn tt_validation_mismatches(&self) -> u32 {
+        self.ctx.tt_mismatches
+    }
+    
+    /// Statistics for the most recent `get_best_move` or `get_master_move` search
+    #[wasm_bindgen]
+    pub fn last_search_stats(&self) -> SearchStats {
+        self.last_stats
+    }
+    
+    /// Whether the most recent move can be trusted despite the node cap: false
+    /// only when that search was capped and a complete shallower search picked
+    /// a different move, a sign to raise the budget. Always true unless
+    /// `set_check_cap_reliability` is on.
+    #[wasm_bindgen]
+    pub fn cap_reliability(&self) -> bool {
+        !self.last_stats.cap_disagreement
+    }
+    
+    /// Get the number of faces on the die used by the search
+    #[wasm_bindgen]
+    pub fn get_die_faces(&self) -> u8 {
+        self.ctx.die_faces
+    }
+    
+    /// Highest score a single column can reach under the current rules
+    /// (three of the highest face; 54 with a standard six-sided die)
+    #[wasm_bindgen]
+    pub fn max_column_score(&self) -> i32 {
+        let faces = self.ctx.die_faces;
+        calculate_column_score(&[faces; 3])
+    }
+    
+    /// Highest score a full grid can reach under the current rules
+    #[wasm_bindgen]
+    pub fn max_grid_score(&self) -> i32 {
+        3 * self.max_column_score()
+    }
+    
+    #[wasm_bindgen]
+    #[allow(clippy::too_many_arguments)]
+    pub fn get_best_move(
+        &mut self,
+        grid1: &[u8],
+        grid2: &[u8],
+        current_player: u8,
+        current_die: u8,
+        depth: u32,
+        randomness: f64,
+        offense_weight: f64,
+        defense_weight: f64,
+        advanced_eval: bool,
+        opponent_depth: u32,
+        opponent_randomness: f64,
+        opponent_offense_weight: f64,
+        opponent_defense_weight: f64,
+        opponent_advanced_eval: bool,
+    ) -> i32 {
+        // Call the extended version with default adversarial=false and time_budget=0
+        self.get_best_move_extended(
+            grid1, grid2, current_player, current_die,
+            depth, randomness, offense_weight, defense_weight, advanced_eval,
+            false, 0.0,  // adversarial, time_budget_ms
+            opponent_depth, opponent_randomness, opponent_offense_weight, opponent_defense_weight, opponent_advanced_eval,
+            false, 0.0,  // opponent adversarial, time_budget_ms
+        )
+    }
+    
+    /// Like `get_best_move`, but also returns the value that selected the move
+    /// and the nodes explored finding it
+    #[wasm_bindgen]
+    #[allow(clippy::too_many_arguments)]
+    pub fn get_best_move_eval(
+        &mut self,
+        grid1: &[u8],
+        grid2: &[u8],
+        current_player: u8,
+        current_die: u8,
+        depth: u32,
+        randomness: f64,
+        offense_weight: f64,
+        defense_weight: f64,
+        advanced_eval: bool,
+        opponent_depth: u32,
+        opponent_randomness: f64,
+        opponent_offense_weight: f64,
+        opponent_defense_weight: f64,
+        opponent_advanced_eval: bool,
+    ) -> MoveEval {
+        let outcome = self.get_best_move_outcome(
+            grid1, grid2, current_player, current_die,
+            depth, randomness, offense_weight, defense_weight, advanced_eval,
+            false, 0.0,  // adversarial, time_budget_ms
+            opponent_depth, opponent_randomness, opponent_offense_weight, opponent_defense_weight, opponent_advanced_eval,
+            false, 0.0,  // opponent adversarial, time_budget_ms
+        );
+        if !outcome.is_ok() {
+            return MoveEval::none();
+        }
+        MoveEval::from_stats(outcome.get_column(), &self.last_stats)
+    }
+    
+    /// Rank every legal column by the same root search `get_best_move` runs, as
+    /// a JSON array of `{"column":c,"value":v}` sorted by value, best first, so a
+    /// hint list agrees with the move the AI would pick. The values come from
+    /// the search that picks the move, with every root move searched to an exact
+    /// value. The picked move always comes first: when the root tie rules
+    /// (basic tiebreak, equal-move window) settle on a column a little below
+    /// another's, they count the two as level. The player's own randomness
+    /// isn't applied; with one legal column the array has a single entry.
+    /// Returns `[]` for invalid input.
+    #[wasm_bindgen]
+    #[allow(clippy::too_many_arguments)]
+    pub fn rank_moves(
+        &mut self,
+        grid1: &[u8],
+        grid2: &[u8],
+        current_player: u8,
+        current_die: u8,
+        depth: u32,
+        offense_weight: f64,
+        defense_weight: f64,
+        advanced_eval: bool,
+        opponent_depth: u32,
+        opponent_randomness: f64,
+        opponent_offense_weight: f64,
+        opponent_defense_weight: f64,
+        opponent_advanced_eval: bool,
+    ) -> String {
+        let state = match placing_state_from_js(grid1, grid2, current_player, current_die, self.ctx.die_faces) {
+            Ok(state) => state,
+            Err(_) => return String::from("[]"),
+        };
+        let player_config = DifficultyConfig {
+            depth,
+            randomness: 0.0,
+            offense_weight,
+            defense_weight,
+            advanced_eval,
+            adversarial: false,
+            time_budget_ms: 0.0,
+            ..self.base_config
+        };
+        // choose_move searches at the min_depth floor too
+        let player_config = DifficultyConfig { depth: player_config.search_depth(), ..player_config };
+        let opponent_config = DifficultyConfig {
+            depth: opponent_depth,
+            randomness: opponent_randomness,
+            offense_weight: opponent_offense_weight,
+            defense_weight: opponent_defense_weight,
+            advanced_eval: opponent_advanced_eval,
+            adversarial: false,
+            time_budget_ms: 0.0,
+            ..self.opponent_base_config
+        };
+        
+        self.ctx.retain_tt_for(state.current_player, &player_config, &opponent_config);
+        self.ctx.begin_search(false, 0.0);
+        // One root search both picks the move and values the others
+        let (played, mut values) = if player_config.depth == 0 {
+            let played = greedy_column(&state, &get_legal_columns(&state), state.current_player, &player_config);
+            (played, evaluate_root_moves(&state, state.current_player, &player_config, &opponent_config, &mut self.ctx))
+        } else {
+            let (played, _, values) = search_root(&state, state.current_player, &player_config, &opponent_config, &mut self.ctx, true);
+            (played, values)
+        };
+        // The played move heads its tie: the root rules only pick a move whose
+        // value the rest don't beat, or one they count as level with the best
+        values.sort_by(|a, b| {
+            (Some(b.0) == played).cmp(&(Some(a.0) == played))
+                .then(b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal))
+        });
+        
+        let entries: Vec<String> = values.iter()
+            .map(|&(col, value)| format!("{{\"column\":{},\"value\":{:.4}}}", col, value))
+            .collect();
+        format!("[{}]", entries.join(","))
+    }
+    
+    /// Get the best move by iterative deepening within `time_budget_ms`: depths
+    /// 1 to `max_depth` are searched in turn, sharing the transposition table,
+    /// and the move from the deepest fully completed depth is played, as
+    /// reported by `last_search_stats().get_depth_reached()`. If not even depth 1
+    /// finishes in time, the greedy one-ply move is played. Plays as the
+    /// Master preset (adversarial, advanced evaluation) with the engine's
+    /// settings; a budget of 0 searches `max_depth` to completion. Returns -1
+    /// for invalid input.
+    #[wasm_bindgen]
+    pub fn get_best_move_timed(
+        &mut self,
+        grid1: &[u8],
+        grid2: &[u8],
+        current_player: u8,
+        current_die: u8,
+        max_depth: u32,
+        time_budget_ms: f64,
+    ) -> i32 {
+        let state = match placing_state_from_js(grid1, grid2, current_player, current_die, self.ctx.die_faces) {
+            Ok(state) => state,
+            Err(_) => return -1,
+        };
+        let preset = DifficultyConfig::master();
+        let config = DifficultyConfig {
+            depth: max_depth,
+            time_budget_ms: if time_budget_ms.is_finite() { time_budget_ms.max(0.0) } else { 0.0 },
+            advanced_eval: preset.advanced_eval,
+            adversarial: preset.adversarial,
+            ..self.base_config
+        };
+        let (col, stats) = choose_move(&state, &config, &config, &mut self.ctx);
+        self.last_stats = stats;
+        col as i32
+    }
+    
+    /// Legacy form of `get_best_move_outcome`: returns the column, or -1 for any
+    /// non-Ok outcome. Unlike the outcome form it reads any nonzero player code
+    /// as Player2 and ignores grid cells past the ninth, as it always has.
+    #[wasm_bindgen]
+    #[allow(clippy::too_many_arguments)]
+    pub fn get_best_move_extended(
+        &mut self,
+        grid1: &[u8],
+        grid2: &[u8],
+        current_player: u8,
+        current_die: u8,
+        depth: u32,
+        randomness: f64,
+        offense_weight: f64,
+        defense_weight: f64,
+        advanced_eval: bool,
+        adversarial: bool,
+        time_budget_ms: f64,
+        opponent_depth: u32,
+        opponent_randomness: f64,
+        opponent_offense_weight: f64,
+        opponent_defense_weight: f64,
+        opponent_advanced_eval: bool,
+        opponent_adversarial: bool,
+        opponent_time_budget_ms: f64,
+    ) -> i32 {
+        let (grid1, grid2, current_player) = legacy_inputs(grid1, grid2, current_player);
+        self.get_best_move_outcome(
+            grid1, grid2, current_player, current_die,
+            depth, randomness, offense_weight, defense_weight, advanced_eval,
+            adversarial, time_budget_ms,
+            opponent_depth, opponent_randomness, opponent_offense_weight, opponent_defense_weight, opponent_advanced_eval,
+            opponent_adversarial, opponent_time_budget_ms,
+        ).get_column()
+    }
+    
+    /// Get the best move along with a status explaining why no move was produced.
+    ///
+    /// With `adversarial` set the opponent is searched as a perfect minimizer and
+    /// the opponent parameters are not used. For deep "setup" play against an
+    /// opponent that only looks one ply ahead, pass `adversarial = false` and
+    /// `opponent_depth = 0`: the search keeps planning the player's own turns to
+    /// full depth while the opponent answers greedily at each of its turns.
+    #[wasm_bindgen]
+    #[allow(clippy::too_many_arguments)]
+    pub fn get_best_move_outcome(
+        &mut self,
+        grid1: &[u8],
+        grid2: &[u8],
+        current_player: u8,
+        current_die: u8,
+        depth: u32,
+        randomness: f64,
+        offense_weight: f64,
+        defense_weight: f64,
+        advanced_eval: bool,
+        adversarial: bool,
+        time_budget_ms: f64,
+        opponent_depth: u32,
+        opponent_randomness: f64,
+        opponent_offense_weight: f64,
+        opponent_defense_weight: f64,
+        opponent_advanced_eval: bool,
+        opponent_adversarial: bool,
+        opponent_time_budget_ms: f64,
+    ) -> MoveOutcome {
+        let state = match placing_state_from_js(grid1, grid2, current_player, current_die, self.ctx.die_faces) {
+            Ok(state) => state,
+            Err(status) => return MoveOutcome::failed(status),
+        };
+        
+        let player_config = DifficultyConfig {
+            depth,
+            randomness,
+            offense_weight,
+            defense_weight,
+            advanced_eval,
+            adversarial,
+            time_budget_ms,
+            ..self.base_config
+        };
+        
+        let opponent_config = DifficultyConfig {
+            depth: opponent_depth,
+            randomness: opponent_randomness,
+            offense_weight: opponent_offense_weight,
+            defense_weight: opponent_defense_weight,
+            advanced_eval: opponent_advanced_eval,
+            adversarial: opponent_adversarial,
+            time_budget_ms: opponent_time_budget_ms,
+            ..self.opponent_base_config
+        };
+        
+        let (col, stats) = choose_move(&state, &player_config, &opponent_config, &mut self.ctx);
+        self.last_stats = stats;
+        MoveOutcome::ok(col)
+    }
+    
+    /// Get the best move in a mirror match: the opponent is modeled with the
+    /// player's own config, as if both sides were equally strong
+    #[wasm_bindgen]
+    #[allow(clippy::too_many_arguments)]
+    pub fn get_best_move_mirror(
+        &mut self,
+        grid1: &[u8],
+        grid2: &[u8],
+        current_player: u8,
+        current_die: u8,
+        depth: u32,
+        randomness: f64,
+        offense_weight: f64,
+        defense_weight: f64,
+        advanced_eval: bool,
+        adversarial: bool,
+        time_budget_ms: f64,
+    ) -> i32 {
+        self.get_best_move_outcome(
+            grid1, grid2, current_player, current_die,
+            depth, randomness, offense_weight, defense_weight, advanced_eval,
+            adversarial, time_budget_ms,
+            depth, randomness, offense_weight, defense_weight, advanced_eval,
+            adversarial, time_budget_ms,
+        ).get_column()
+    }
+    
+    /// Get the best move playing as a named personality, modeling the opponent as
+    /// a balanced player. Returns -1 when no move is possible.
+    #[wasm_bindgen]
+    pub fn get_best_move_personality(
+        &mut self,
+        grid1: &[u8],
+        grid2: &[u8],
+        current_player: u8,
+        current_die: u8,
+        personality: Personality,
+    ) -> i32 {
+        let state = match placing_state_from_js(grid1, grid2, current_player, current_die, self.ctx.die_faces) {
+            Ok(state) => state,
+            Err(_) => return -1,
+        };
+        let player_config = DifficultyConfig {
+            min_depth: self.base_config.min_depth,
+            ..personality.config()
+        };
+        let opponent_config = Personality::Balanced.config();
+        
+        let (col, stats) = choose_move(&state, &player_config, &opponent_config, &mut self.ctx);
+        self.last_stats = stats;
+        col as i32
+    }
+    
+    /// Get the best move together with the opponent's likely reply, as
+    /// `[my_col, reply_col]`, with both sides using the given config.
+    ///
+    /// The reply depends on the opponent's next roll, so it is the column their
+    /// search picks for the most rolls; -1 when several columns are equally
+    /// likely or the game ends with our move. Both columns are -1 for invalid
+    /// input. Randomness is not applied, so the preview is deterministic.
+    #[wasm_bindgen]
+    #[allow(clippy::too_many_arguments)]
+    pub fn best_move_and_reply(
+        &mut self,
+        grid1: &[u8],
+        grid2: &[u8],
+        current_player: u8,
+        current_die: u8,
+        depth: u32,
+        offense_weight: f64,
+        defense_weight: f64,
+        advanced_eval: bool,
+        adversarial: bool,
+    ) -> Vec<i32> {
+        let state = match placing_state_from_js(grid1, grid2, current_player, current_die, self.ctx.die_faces) {
+            Ok(state) => state,
+            Err(_) => return vec![-1, -1],
+        };
+        let config = DifficultyConfig {
+            depth,
+            offense_weight,
+            defense_weight,
+            advanced_eval,
+            adversarial,
+            ..self.base_config
+        };
+        
+        let (my_col, stats) = choose_move(&state, &config, &config, &mut self.ctx);
+        self.last_stats = stats;
+        let after = match apply_move(&state, my_col) {
+            Some(after) if after.phase != GamePhase::Ended => after,
+            _ => return vec![my_col as i32, -1],
+        };
+        
+        let mut reply_counts = [0u32; 3];
+        for die_value in 1..=self.ctx.die_faces {
+            let (reply, _) = choose_move(&roll_die(&after, die_value), &config, &config, &mut self.ctx);
+            reply_counts[reply] += 1;
+        }
+        let most = reply_counts.iter().copied().max().unwrap_or(0);
+        let mut likely = (0..3).filter(|&col| reply_counts[col] == most);
+        let reply = match (likely.next(), likely.next()) {
+            (Some(col), None) => col as i32,
+            _ => -1,
+        };
+        vec![my_col as i32, reply]
+    }
+    
+    /// Get the best move for a continuous difficulty slider: 0.0 plays like the
+    /// Beginner preset, 1.0 like Master, with configs blended in between. The
+    /// opponent is modeled with the same blended config. Returns -1 when no move
+    /// is possible.
+    #[wasm_bindgen]
+    pub fn get_best_move_slider(
+        &mut self,
+        grid1: &[u8],
+        grid2: &[u8],
+        current_player: u8,
+        current_die: u8,
+        difficulty: f64,
+    ) -> i32 {
+        let state = match placing_state_from_js(grid1, grid2, current_player, current_die, self.ctx.die_faces) {
+            Ok(state) => state,
+            Err(_) => return -1,
+        };
+        let blended = DifficultyConfig::lerp(&DifficultyConfig::beginner(), &DifficultyConfig::master(), difficulty);
+        let player_config = DifficultyConfig {
+            min_depth: blended.min_depth.max(self.base_config.min_depth),
+            ..blended
+        };
+        
+        let (col, stats) = choose_move(&state, &player_config, &blended, &mut self.ctx);
+        self.last_stats = stats;
+        col as i32
+    }
+    
+    /// Get the best move while modeling the opponent as `opponent_handicap` plies
+    /// shallower than the player. The opponent depth floors at 0, which makes the
+    /// search assume a greedy opponent. Both sides use balanced weights and the
+    /// advanced evaluation.
+    #[wasm_bindgen]
+    pub fn get_best_move_handicap(
+        &mut self,
+        grid1: &[u8],
+        grid2: &[u8],
+        current_player: u8,
+        current_die: u8,
+        player_depth: u32,
+        opponent_handicap: u32,
+    ) -> i32 {
+        self.get_best_move(
+            grid1, grid2, current_player, current_die,
+            player_depth, 0.0, 0.5, 0.5, true,
+            player_depth.saturating_sub(opponent_handicap), 0.0, 0.5, 0.5, true,
+        )
+    }
+    
+    /// Get the best move when the opponent's grid is hidden, from the player's own
+    /// grid alone. Returns -1 for invalid input or when no move is possible.
+    ///
+    /// Assumed opponent model: the hidden grid is treated as empty, so it always
+    /// has room in every column and our placements capture nothing from it, while
+    /// the threat terms still count every die of ours as attackable. In the
+    /// lookahead the opponent plays greedily, capturing our matching dice
+    /// whenever their roll allows. The search is balanced with the advanced
+    /// evaluation.
+    #[wasm_bindgen]
+    pub fn get_best_move_blind(
+        &mut self,
+        grid_self: &[u8],
+        player: u8,
+        current_die: u8,
+        depth: u32,
+    ) -> i32 {
+        let (grid1, grid2): (&[u8], &[u8]) = if player == 0 { (grid_self, &[]) } else { (&[], grid_self) };
+        let state = match placing_state_from_js(grid1, grid2, player, current_die, self.ctx.die_faces) {
+            Ok(state) => state,
+            Err(_) => return -1,
+        };
+        
+        let player_config = DifficultyConfig {
+            depth,
+            advanced_eval: true,
+            ..self.base_config
+        };
+        let opponent_config = DifficultyConfig {
+            advanced_eval: true,
+            ..DifficultyConfig::default()
+        };
+        
+        let (col, stats) = choose_move(&state, &player_config, &opponent_config, &mut self.ctx);
+        self.last_stats = stats;
+        col as i32
+    }
+    
+    /// Get the best column for Player1-to-move and for Player2-to-move from the
+    /// same grids and die, as `[player1_col, player2_col]` (-1 where none exists).
+    ///
+    /// Both searches use balanced weights with the advanced evaluation and model
+    /// the opponent with the same config. The transposition table is shared:
+    /// keys include the side to move and node type, so entries from one
+    /// perspective never collide with the other's.
+    #[wasm_bindgen]
+    pub fn best_moves_both(
+        &mut self,
+        grid1: &[u8],
+        grid2: &[u8],
+        current_die: u8,
+        depth: u32,
+    ) -> Vec<i32> {
+        (0..2u8)
+            .map(|player| {
+                self.get_best_move(
+                    grid1, grid2, player, current_die,
+                    depth, 0.0, 0.5, 0.5, true,
+                    depth, 0.0, 0.5, 0.5, true,
+                )
+            })
+            .collect()
+    }
+    
+    /// Get the best move using Master AI with adaptive weights from opponent
+    /// profile. Reads its inputs as leniently as `get_best_move_extended`.
+    #[wasm_bindgen]
+    pub fn get_master_move(
+        &mut self,
+        grid1: &[u8],
+        grid2: &[u8],
+        current_player: u8,
+        current_die: u8,
+        profile: &OpponentProfile,
+    ) -> i32 {
+        let (grid1, grid2, current_player) = legacy_inputs(grid1, grid2, current_player);
+        self.get_master_move_outcome(grid1, grid2, current_player, current_die, profile).get_column()
+    }
+    
+    /// Like `get_master_move`, but also returns the value that selected the move
+    /// (including the profile's column bonus) and the nodes explored finding it
+    #[wasm_bindgen]
+    pub fn get_master_move_eval(
+        &mut self,
+        grid1: &[u8],
+        grid2: &[u8],
+        current_player: u8,
+        current_die: u8,
+        profile: &OpponentProfile,
+    ) -> MoveEval {
+        let outcome = self.get_master_move_outcome(grid1, grid2, current_player, current_die, profile);
+        if !outcome.is_ok() {
+            return MoveEval::none();
+        }
+        MoveEval::from_stats(outcome.get_column(), &self.last_stats)
+    }
+    
+    /// Get the Master AI move along with a status explaining why no move was produced
+    #[wasm_bindgen]
+    pub fn get_master_move_outcome(
+        &mut self,
+        grid1: &[u8],
+        grid2: &[u8],
+        current_player: u8,
+        current_die: u8,
+        profile: &OpponentProfile,
+    ) -> MoveOutcome {
+        let state = match placing_state_from_js(grid1, grid2, current_player, current_die, self.ctx.die_faces) {
+            Ok(state) => state,
+            Err(status) => return MoveOutcome::failed(status),
+        };
+        
+        let player = state.current_player;
+        let legal_columns = get_legal_columns(&state);
+        
+        if legal_columns.len() == 1 {
+            self.last_stats = SearchStats { value: f64::NAN, ..SearchStats::default() };
+            return MoveOutcome::ok(legal_columns[0]);
+        }
+        
+        // Get adaptive config from profile
+        let adaptive_config = profile.get_adaptive_config();
+        
+        // Use expert-level opponent modeling with adversarial search
+        let opponent_config = DifficultyConfig {
+            depth: 3,
+            randomness: 0.0,
+            offense_weight: 0.5,
+            defense_weight: 0.5,
+            advanced_eval: true,
+            adversarial: true,
+            time_budget_ms: 0.0,
+            ..DifficultyConfig::default()
+        };
+        
+        // Setup context for adversarial search, 100ms budget for master. The
+        // table is kept only while the adapted config stays the same.
+        self.ctx.retain_tt_for(player, &adaptive_config, &opponent_config);
+        self.ctx.begin_search(true, 100.0);
+        
+        // Order moves with adaptive bias from profile
+        let ordered = order_moves_with_profile(&state, &legal_columns, player, profile);
+        let mut best_move: Option<usize> = None;
+        let mut best_value = f64::NEG_INFINITY;
+        
+        for col in ordered {
+            if self.ctx.should_abort() {
+                break;
+            }
+            
+            if let Some(new_state) = apply_move(&state, col) {
+                let base_value = if new_state.phase == GamePhase::Ended {
+                    evaluate(&new_state, player, &adaptive_config)
+                } else {
+                    chance_node(&new_state, adaptive_config.depth.saturating_sub(1), player, &adaptive_config, &opponent_config, &mut self.ctx)
+                };
+                
+                // Apply column bias from learned opponent patterns
+                let column_bias = profile.get_column_attack_bonus(col);
+                let value = base_value + column_bias;
+                
+                if value > best_value {
+                    best_value = value;
+                    best_move = Some(col);
+                }
+            }
+        }
+        
+        self.last_stats = SearchStats {
+            nodes: self.ctx.nodes_explored,
+            tt_hits: self.ctx.tt_hits,
+            tt_probes: self.ctx.tt_probes,
+            truncated: self.ctx.aborted || self.ctx.nodes_explored > self.ctx.max_nodes,
+            elapsed_ms: js_sys::Date::now() - self.ctx.start_time,
+            value: if best_move.is_some() { best_value } else { f64::NAN },
+            ..SearchStats::default()
+        };
+        
+        MoveOutcome::ok(best_move.unwrap_or(legal_columns[0]))
+    }
+}
+
+// ============================================================================
+// Master AI - Opponent Profile for Adaptive Learning
+// ============================================================================
+
+// Constants for Master AI adaptive learning
+/// Default frequency when no data available (1/3 for 3 columns)
+const UNIFORM_COLUMN_FREQUENCY: f64 = 0.333;
+
+/// Scaling factor for column preference bonus based on opponent usage patterns
+const COLUMN_PREFERENCE_SCALE: f64 = 3.0;
+
+/// Scaling factor for high dice placement bonus (targets opponent's high-value columns)
+const HIGH_DICE_BONUS_SCALE: f64 = 5.0;
+
+/// Scaling factor for the interaction bonus on a column the opponent both favors
+/// and loads with high dice (their key scoring column)
+const KEY_COLUMN_INTERACTION_SCALE: f64 = 10.0;
+
+/// Multiplier for profile-based bonus in move ordering (balances learned patterns vs immediate value)
+const PROFILE_BONUS_MULTIPLIER: f64 = 2.0;
+
+/// Attack rate threshold for aggressive opponent detection
+const AGGRESSIVE_ATTACK_THRESHOLD: f64 = 0.4;
+
+/// Attack rate threshold for passive opponent detection  
+const PASSIVE_ATTACK_THRESHOLD: f64 = 0.2;
+
+/// Largest deviation of any column's usage share from 1/3 that still counts as
+/// uniform play for the insufficient-signal check
+const UNIFORM_USAGE_TOLERANCE: f64 = 0.08;
+
+/// Extra search depth when the profile can't tell what kind of player this is
+const INSUFFICIENT_SIGNAL_DEPTH_BONUS: u32 = 1;
+
+/// Games after which the profile's per-game confidence saturates
+const LEARNING_GAMES_TARGET: f64 = 3.0;
+
+/// Move count scale for the sample-size confidence (1 - e^(-moves/scale))
+const LEARNING_MOVES_SCALE: f64 = 30.0;
+
+/// Share of learning progress credited to a well-sampled profile even when the
+/// opponent's column usage is indistinguishable from uniform
+const UNIFORM_PLAY_PROGRESS: f64 = 0.6;
+
+/// Current version of the serialized profile blob written by `export_full`
+/// (version 2 added the per-column die sums and counts, version 3 the score
+/// lost per column)
+const PROFILE_BLOB_VERSION: u8 = 3;
+
+/// Little-endian cursor over a serialized profile blob
+struct BlobReader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> BlobReader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        BlobReader { data, pos: 0 }
+    }
+
+    fn read_u8(&mut self) -> Option<u8> {
+        let value = *self.data.get(self.pos)?;
+        self.pos += 1;
+        Some(value)
+    }
+
+    fn read_u32(&mut self) -> Option<u32> {
+        let bytes = self.data.get(self.pos..self.pos + 4)?;
+        self.pos += 4;
+        Some(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    fn read_u32_array(&mut self) -> Option<[u32; 3]> {
+        Some([self.read_u32()?, self.read_u32()?, self.read_u32()?])
+    }
+}
+
+fn write_u32_array(out: &mut Vec<u8>, values: &[u32; 3]) {
+    for value in values {
+        out.extend_from_slice(&value.to_le_bytes());
+    }
+}
+
+/// Opponent behavior profile that learns patterns across games
+#[derive(Clone)]
+#[wasm_bindgen]
+pub struct OpponentProfile {
+    // Column usage frequency [col0, col1, col2]
+    column_usage: [u32; 3],
+    total_moves: u32,
+    
+    // Attack stats: times opponent removed dice
+    attack_moves: u32,
+    
+    // Die placement patterns by column
+    // High dice (5-6) placements per column
+    high_dice_placements: [u32; 3],
+    // Low dice (1-2) placements per column
+    low_dice_placements: [u32; 3],
+    
+    // Total score lost to opponent attacks (for defense learning)
+    score_lost_to_attacks: u32,
+    // The same, split by which of our columns was hit
+    score_lost_by_column: [u32; 3],
+    
+    // Sum and count of die values placed per column, for the mean die value
+    sum_die_by_column: [u32; 3],
+    count_die_by_column: [u32; 3],
+    
+    // Games completed for stability weighting
+    games_completed: u32,
+    
+    // Tuning for the attack bonus; not learned, so not serialized or reset
+    attack_scales: AttackBonusScales,
+    // Search deeper instead of staying balanced when play is uninformative;
+    // a setting like attack_scales
+    deepen_on_weak_signal: bool,
+    // Apply the column attack bonus throughout the search, not just at the root
+    propagate_column_bias: bool,
+    
+    // Moves recorded since the last end_game, and the recap it produced
+    current_game: GameTally,
+    last_summary: Option<GameSummary>,
+    // Adaptive (offense, defense) weights when the current game's first move
+    // was recorded, for end_game to compare against
+    weights_at_game_start: Option<(f64, f64)>,
+}
+
+/// Opponent activity within a single game
+#[derive(Clone, Copy, Debug, Default)]
+struct GameTally {
+    column_usage: [u32; 3],
+    moves: u32,
+    attack_moves: u32,
+    score_lost: u32,
+}
+
+/// Recap of one finished game, captured by `end_game`
+#[derive(Clone, Copy, Debug)]
+struct GameSummary {
+    tally: GameTally,
+    offense_weight: f64,
+    defense_weight: f64,
+    model_shifted: bool,
+}
+
+/// Scaling constants for `get_column_attack_bonus`, tunable per profile
+#[derive(Clone, Copy, Debug)]
+struct AttackBonusScales {
+    column_preference: f64,
+    high_dice: f64,
+    key_column: f64,
+}
+
+impl Default for AttackBonusScales {
+    fn default() -> Self {
+        AttackBonusScales {
+            column_preference: COLUMN_PREFERENCE_SCALE,
+            high_dice: HIGH_DICE_BONUS_SCALE,
+            key_column: KEY_COLUMN_INTERACTION_SCALE,
+        }
+    }
+}
+
+impl Default for OpponentProfile {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[wasm_bindgen]
+impl OpponentProfile {
+    /// Create a new empty opponent profile
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> Self {
+        OpponentProfile {
+            column_usage: [0; 3],
+            total_moves: 0,
+            attack_moves: 0,
+            high_dice_placements: [0; 3],
+            low_dice_placements: [0; 3],
+            score_lost_to_attacks: 0,
+            score_lost_by_column: [0; 3],
+            sum_die_by_column: [0; 3],
+            count_die_by_column: [0; 3],
+            games_completed: 0,
+            attack_scales: AttackBonusScales::default(),
+            deepen_on_weak_signal: false,
+            propagate_column_bias: false,
+            current_game: GameTally::default(),
+            last_summary: None,
+            weights_at_game_start: None,
+        }
+    }
+    
+    /// Record an opponent move for learning. Dice up to the largest supported
+    /// die are accepted so variant games can be learned from.
+    #[wasm_bindgen]
+    pub fn record_move(&mut self, col: u8, die_value: u8, removed_count: u8, score_lost: u32) {
+        if col > 2 || die_value == 0 || die_value > MAX_DIE_FACES {
+            return;
+        }
+        
+        let col_idx = col as usize;
+        
+        if self.current_game.moves == 0 {
+            self.weights_at_game_start = Some(self.adaptive_weights());
+        }
+        
+        self.column_usage[col_idx] = self.column_usage[col_idx].saturating_add(1);
+        self.total_moves = self.total_moves.saturating_add(1);
+        
+        if removed_count > 0 {
+            self.attack_moves = self.attack_moves.saturating_add(1);
+            self.score_lost_to_attacks = self.score_lost_to_attacks.saturating_add(score_lost);
+            // A capture always hits our column with the same index
+            self.score_lost_by_column[col_idx] = self.score_lost_by_column[col_idx].saturating_add(score_lost);
+        }
+        
+        let game = &mut self.current_game;
+        game.column_usage[col_idx] = game.column_usage[col_idx].saturating_add(1);
+        game.moves = game.moves.saturating_add(1);
+        if removed_count > 0 {
+            game.attack_moves = game.attack_moves.saturating_add(1);
+            game.score_lost = game.score_lost.saturating_add(score_lost);
+        }
+        
+        self.sum_die_by_column[col_idx] = self.sum_die_by_column[col_idx].saturating_add(die_value as u32);
+        self.count_die_by_column[col_idx] = self.count_die_by_column[col_idx].saturating_add(1);
+        
+        if die_value >= 5 {
+            self.high_dice_placements[col_idx] = self.high_dice_placements[col_idx].saturating_add(1);
+        } else if die_value <= 2 {
+            self.low_dice_placements[col_idx] = self.low_dice_placements[col_idx].saturating_add(1);
+        }
+    }
+    
+    /// Set the weight of the opponent's column preference in the attack bonus
+    #[wasm_bindgen]
+    pub fn set_column_preference_scale(&mut self, scale: f64) {
+        self.attack_scales.column_preference = if scale.is_finite() { scale } else { COLUMN_PREFERENCE_SCALE };
+    }
+    
+    /// Set the weight of the opponent's high-dice placements in the attack bonus
+    #[wasm_bindgen]
+    pub fn set_high_dice_bonus_scale(&mut self, scale: f64) {
+        self.attack_scales.high_dice = if scale.is_finite() { scale } else { HIGH_DICE_BONUS_SCALE };
+    }
+    
+    /// Set the weight of the extra bonus for a column that is both favored and
+    /// loaded with high dice (0 disables the interaction)
+    #[wasm_bindgen]
+    pub fn set_key_column_scale(&mut self, scale: f64) {
+        self.attack_scales.key_column = if scale.is_finite() { scale } else { KEY_COLUMN_INTERACTION_SCALE };
+    }
+    
+    /// When the opponent's attack rate is mid-range and they spread their dice
+    /// evenly, there is nothing to adapt the weights to; with this on the
+    /// adaptive config searches one ply deeper instead. Off by default.
+    #[wasm_bindgen]
+    pub fn set_deepen_on_weak_signal(&mut self, enabled: bool) {
+        self.deepen_on_weak_signal = enabled;
+    }
+    
+    /// Apply the learned column attack bonus to the Master AI's own placements
+    /// throughout its search instead of only to the root move, so it can plan
+    /// several turns of exploiting a predictable opponent. Off by default.
+    #[wasm_bindgen]
+    pub fn set_propagate_column_bias(&mut self, enabled: bool) {
+        self.propagate_column_bias = enabled;
+    }
+    
+    /// Learned bonus for attacking column `col` (0-2), as used by the Master AI's
+    /// move ordering. 0.0 for invalid columns or before enough moves are recorded.
+    #[wasm_bindgen]
+    pub fn column_attack_bonus(&self, col: u8) -> f64 {
+        self.get_column_attack_bonus(col as usize)
+    }
+    
+    /// How the Master AI would currently play against this opponent, as
+    /// `[offense_weight, defense_weight, depth]` from the adaptive config
+    #[wasm_bindgen]
+    pub fn adaptive_stance(&self) -> Vec<f64> {
+        let config = self.get_adaptive_config();
+        vec![config.offense_weight, config.defense_weight, config.depth as f64]
+    }
+    
+    /// Mark end of game for stability tracking
+    #[wasm_bindgen]
+    pub fn end_game(&mut self) {
+        // The adaptive weights as they stood before this game's first move
+        let before = self.weights_at_game_start.take().unwrap_or_else(|| self.adaptive_weights());
+        self.games_completed = self.games_completed.saturating_add(1);
+        let (offense_weight, defense_weight) = self.adaptive_weights();
+        
+        self.last_summary = Some(GameSummary {
+            tally: self.current_game,
+            offense_weight,
+            defense_weight,
+            model_shifted: before != (offense_weight, defense_weight),
+        });
+        self.current_game = GameTally::default();
+    }
+    
+    /// Recap of the most recently finished game as JSON: the opponent's move
+    /// count and attack rate in that game, their most-used column (-1 if they made
+    /// no moves), points they removed, the adapted offense/defense weights and
+    /// personality afterwards, and whether that game changed the adapted weights.
+    /// Returns "null" before the first `end_game`.
+    #[wasm_bindgen]
+    pub fn end_game_summary(&self) -> String {
+        let summary = match &self.last_summary {
+            Some(summary) => summary,
+            None => return "null".to_string(),
+        };
+        let tally = &summary.tally;
+        let attack_rate = if tally.moves > 0 {
+            tally.attack_moves as f64 / tally.moves as f64
+        } else {
+            0.0
+        };
+        let most_used_column = if tally.moves > 0 {
+            (0..3).max_by_key(|&col| (tally.column_usage[col], std::cmp::Reverse(col))).map_or(-1, |col| col as i32)
+        } else {
+            -1
+        };
+        let personality = if summary.defense_weight > summary.offense_weight {
+            "defensive"
+        } else if summary.offense_weight > summary.defense_weight {
+            "offensive"
+        } else {
+            "balanced"
+        };
+        
+        format!(
+            "{{\"moves\":{},\"attack_rate\":{:.3},\"most_used_column\":{},\"points_removed\":{},\"offense_weight\":{:.3},\"defense_weight\":{:.3},\"personality\":\"{}\",\"model_shifted\":{}}}",
+            tally.moves,
+            attack_rate,
+            most_used_column,
+            tally.score_lost,
+            summary.offense_weight,
+            summary.defense_weight,
+            personality,
+            summary.model_shifted
+        )
+    }
+    
+    /// Reset all learned data
+    #[wasm_bindgen]
+    pub fn reset(&mut self) {
+        self.column_usage = [0; 3];
+        self.total_moves = 0;
+        self.attack_moves = 0;
+        self.high_dice_placements = [0; 3];
+        self.low_dice_placements = [0; 3];
+        self.score_lost_to_attacks = 0;
+        self.score_lost_by_column = [0; 3];
+        self.sum_die_by_column = [0; 3];
+        self.count_die_by_column = [0; 3];
+        self.games_completed = 0;
+        self.current_game = GameTally::default();
+        self.last_summary = None;
+        self.weights_at_game_start = None;
+    }
+    
+    /// Get the number of games completed
+    #[wasm_bindgen]
+    pub fn get_games_completed(&self) -> u32 {
+        self.games_completed
+    }
+    
+    /// Get total moves recorded
+    #[wasm_bindgen]
+    pub fn get_total_moves(&self) -> u32 {
+        self.total_moves
+    }
+    
+    /// Get attack rate (0.0 to 1.0)
+    #[wasm_bindgen]
+    pub fn get_attack_rate(&self) -> f64 {
+        if self.total_moves == 0 {
+            return 0.0;
+        }
+        self.attack_moves as f64 / self.total_moves as f64
+    }
+    
+    /// Get the total score the opponent's captures have removed from our column `col`
+    #[wasm_bindgen]
+    pub fn get_score_lost_in_column(&self, col: u8) -> u32 {
+        if col > 2 {
+            return 0;
+        }
+        self.score_lost_by_column[col as usize]
+    }
+    
+    /// Get the mean die value the opponent has placed in a column (0.0 if none)
+    #[wasm_bindgen]
+    pub fn get_average_die(&self, col: u8) -> f64 {
+        if col > 2 || self.count_die_by_column[col as usize] == 0 {
+            return 0.0;
+        }
+        self.sum_die_by_column[col as usize] as f64 / self.count_die_by_column[col as usize] as f64
+    }
+    
+    /// Get column usage frequency for a column (0.0 to 1.0)
+    #[wasm_bindgen]
+    pub fn get_column_frequency(&self, col: u8) -> f64 {
+        if col > 2 {
+            return 0.0;
+        }
+        if self.total_moves == 0 {
+            return UNIFORM_COLUMN_FREQUENCY;
+        }
+        self.column_usage[col as usize] as f64 / self.total_moves as f64
+    }
+    
+    /// Get how much the profile has learned about the opponent (0.0 to 1.0).
+    ///
+    /// Combines sample-size confidence (games completed and moves seen) with the
+    /// KL divergence of the column-usage distribution from uniform, normalized by
+    /// ln 3. A well-sampled profile of an opponent who plays uniformly still
+    /// reports substantial progress, since that is itself a learned fact;
+    /// lopsided play pushes progress towards 1.0.
+    #[wasm_bindgen]
+    pub fn learning_progress(&self) -> f64 {
+        if self.total_moves == 0 {
+            return 0.0;
+        }
+        
+        let game_confidence = (self.games_completed as f64 / LEARNING_GAMES_TARGET).min(1.0);
+        let move_confidence = 1.0 - (-(self.total_moves as f64) / LEARNING_MOVES_SCALE).exp();
+        let confidence = 0.5 * game_confidence + 0.5 * move_confidence;
+        
+        let uniform = 1.0 / 3.0;
+        let divergence: f64 = self.column_usage.iter()
+            .map(|&count| count as f64 / self.total_moves as f64)
+            .filter(|&p| p > 0.0)
+            .map(|p| p * (p / uniform).ln())
+            .sum();
+        let divergence = (divergence / 3f64.ln()).clamp(0.0, 1.0);
+        
+        confidence * (UNIFORM_PLAY_PROGRESS + (1.0 - UNIFORM_PLAY_PROGRESS) * divergence)
+    }
+    
+    /// Serialize every learned field into a versioned blob for persistence
+    #[wasm_bindgen]
+    pub fn export_full(&self) -> Vec<u8> {
+        let mut out = vec![PROFILE_BLOB_VERSION];
+        write_u32_array(&mut out, &self.column_usage);
+        out.extend_from_slice(&self.total_moves.to_le_bytes());
+        out.extend_from_slice(&self.attack_moves.to_le_bytes());
+        write_u32_array(&mut out, &self.high_dice_placements);
+        write_u32_array(&mut out, &self.low_dice_placements);
+        out.extend_from_slice(&self.score_lost_to_attacks.to_le_bytes());
+        out.extend_from_slice(&self.games_completed.to_le_bytes());
+        write_u32_array(&mut out, &self.sum_die_by_column);
+        write_u32_array(&mut out, &self.count_die_by_column);
+        write_u32_array(&mut out, &self.score_lost_by_column);
+        out
+    }
+    
+    /// Restore a profile from a blob written by `export_full`.
+    /// Fields added after the blob's version keep their defaults. Returns false
+    /// (leaving the profile unchanged) for truncated or unknown-version blobs.
+    #[wasm_bindgen]
+    pub fn import_full(&mut self, data: &[u8]) -> bool {
+        match OpponentProfile::parse_blob(data) {
+            Some(profile) => {
+                *self = OpponentProfile {
+                    attack_scales: self.attack_scales,
+                    deepen_on_weak_signal: self.deepen_on_weak_signal,
+                    propagate_column_bias: self.propagate_column_bias,
+                    ..profile
+                };
+                true
+            }
+            None => false,
+        }
+    }
+    
+    /// Learn from a recorded game transcript as if its moves had been recorded live.
+    /// Each placement by `opponent_player` (0 or 1) is recorded with the captures it
+    /// made against the replayed board, and a finished game counts towards
+    /// `games_completed`. Returns false (leaving the profile unchanged) if the
+    /// transcript is malformed.
+    #[wasm_bindgen]
+    pub fn learn_from_transcript(&mut self, transcript: &[u8], opponent_player: u8) -> bool {
+        if opponent_player > 1 {
+            return false;
+        }
+        let plies = match replay_transcript(transcript, DEFAULT_DIE_FACES) {
+            Some(plies) => plies,
+            None => return false,
+        };
+        let opponent = if opponent_player == 0 { Player::Player1 } else { Player::Player2 };
+        
+        for (state, col) in &plies {
+            if state.current_player != opponent {
+                continue;
+            }
+            let die_value = state.current_die.unwrap_or(0);
+            let victim = match opponent {
+                Player::Player1 => &state.grid2,
+                Player::Player2 => &state.grid1,
+            };
+            let removed_count = victim.column(*col).iter().filter(|&&v| v == die_value).count() as u8;
+            let score_lost = calculate_opponent_score_loss(victim, *col, die_value).max(0) as u32;
+            self.record_move(*col as u8, die_value, removed_count, score_lost);
+        }
+        
+        let finished = plies.last()
+            .and_then(|(state, col)| apply_move(state, *col))
+            .is_some_and(|state| state.phase == GamePhase::Ended);
+        if finished {
+            self.end_game();
+        }
+        true
+    }
+}
+
+impl OpponentProfile {
+    /// Parse a versioned profile blob into a fresh profile
+    fn parse_blob(data: &[u8]) -> Option<OpponentProfile> {
+        let mut reader = BlobReader::new(data);
+        let version = reader.read_u8()?;
+        if version == 0 || version > PROFILE_BLOB_VERSION {
+            return None;
+        }
+        
+        let mut profile = OpponentProfile::new();
+        profile.column_usage = reader.read_u32_array()?;
+        profile.total_moves = reader.read_u32()?;
+        profile.attack_moves = reader.read_u32()?;
+        profile.high_dice_placements = reader.read_u32_array()?;
+        profile.low_dice_placements = reader.read_u32_array()?;
+        profile.score_lost_to_attacks = reader.read_u32()?;
+        profile.games_completed = reader.read_u32()?;
+        if version >= 2 {
+            profile.sum_die_by_column = reader.read_u32_array()?;
+            profile.count_die_by_column = reader.read_u32_array()?;
+        }
+        if version >= 3 {
+            profile.score_lost_by_column = reader.read_u32_array()?;
+        }
+        Some(profile)
+    }
+    
+    /// The adaptive config's (offense, defense) weights
+    fn adaptive_weights(&self) -> (f64, f64) {
+        let config = self.get_adaptive_config();
+        (config.offense_weight, config.defense_weight)
+    }
+    
+    /// Calculate adaptive difficulty config based on learned opponent patterns.
+    fn get_adaptive_config(&self) -> DifficultyConfig {
+        let mut config = DifficultyConfig {
+            depth: 5,
+            randomness: 0.0,
+            offense_weight: 0.5,
+            defense_weight: 0.5,
+            advanced_eval: true,
+            adversarial: true,
+            time_budget_ms: 100.0,
+            ..DifficultyConfig::default()
+        };
+        
+        if self.propagate_column_bias {
+            config.column_bias = std::array::from_fn(|col| self.get_column_attack_bonus(col));
+        }
+        
+        if self.games_completed < 3 || self.total_moves < 10 {
+            return config;
+        }
+        
+        let attack_rate = self.get_attack_rate();
+        
+        if attack_rate > AGGRESSIVE_ATTACK_THRESHOLD {
+            config.defense_weight = (0.6 + (attack_rate - AGGRESSIVE_ATTACK_THRESHOLD) * 0.5).clamp(0.0, 1.0);
+            config.offense_weight = 1.0 - config.defense_weight;
+        } else if attack_rate < PASSIVE_ATTACK_THRESHOLD {
+            config.offense_weight = 0.7;
+            config.defense_weight = 0.3;
+        } else if self.deepen_on_weak_signal && self.has_uniform_column_usage() {
+            // Insufficient signal: keep the balanced weights but play stronger
+            config.depth += INSUFFICIENT_SIGNAL_DEPTH_BONUS;
+        }
+        
+        config
+    }
+    
+    /// Whether every column's share of the opponent's moves is close to a third
+    fn has_uniform_column_usage(&self) -> bool {
+        self.total_moves > 0 && self.column_usage.iter().all(|&count| {
+            (count as f64 / self.total_moves as f64 - 1.0 / 3.0).abs() <= UNIFORM_USAGE_TOLERANCE
+        })
+    }
+    
+    /// Get bonus for attacking a specific column based on opponent patterns.
+    fn get_column_attack_bonus(&self, col: usize) -> f64 {
+        if self.total_moves < 10 || col > 2 {
+            return 0.0;
+        }
+        
+        let col_freq = self.column_usage[col] as f64 / self.total_moves as f64;
+        let preference_delta = col_freq - UNIFORM_COLUMN_FREQUENCY;
+        
+        let total_high_dice: u32 = self.high_dice_placements.iter().sum();
+        let high_dice_ratio = if total_high_dice > 0 {
+            self.high_dice_placements[col] as f64 / total_high_dice as f64
+        } else {
+            UNIFORM_COLUMN_FREQUENCY
+        };
+        
+        let high_dice_delta = high_dice_ratio - UNIFORM_COLUMN_FREQUENCY;
+        let scales = &self.attack_scales;
+        
+        // A column that is both favored and loaded with high dice is the opponent's
+        // key scoring column, so the two signals compound rather than just add
+        let interaction = preference_delta.max(0.0) * high_dice_delta.max(0.0) * scales.key_column;
+        
+        preference_delta * scales.column_preference + high_dice_delta * scales.high_dice + interaction
+    }
+}
+
+/// Order moves considering both quick evaluation and profile-based bias.
+fn order_moves_with_profile(
+    state: &GameState, 
+    columns: &[usize], 
+    player: Player,
+    profile: &OpponentProfile,
+) -> Vec<usize> {
+    if let Some(die_value) = state.current_die {
+        let mut scored: Vec<(usize, f64)> = columns.iter()
+            .map(|&col| {
+                let base_score = order_score(state, col, die_value, player) as f64;
+                let profile_bonus = profile.get_column_attack_bonus(col);
+                (col, base_score + profile_bonus * PROFILE_BONUS_MULTIPLIER)
+            })
+            .collect();
+        scored.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+        scored.into_iter().map(|(col, _)| col).collect()
+    } else {
+        columns.to_vec()
+    }
+}
+
+// ============================================================================
+// Neural Network for Policy/Value Prediction
+// ============================================================================
+
+/// State encoding size:
+/// - 36 features for each player's grid (6 possible die values × 3 columns × 2 for count encoding)
+/// - Actually: 3 cols × 3 rows × 7 (one-hot for 0-6) × 2 players = 126
+/// - Simplified: 18 (grid1) + 18 (grid2) + 1 (current player) + 6 (current die one-hot) = 43
+///
+/// Using compact encoding: counts per face per column for both players
+/// 6 faces × 3 columns × 2 players = 36 + 1 (player) + 6 (die one-hot) = 43
+const STATE_ENCODING_SIZE: usize = 43;
+const HIDDEN_SIZE: usize = 128;  // Doubled from 64 for more capacity
+const POLICY_OUTPUT_SIZE: usize = 3;  // 3 columns
+
+/// Encode game state into feature vector
+fn encode_state(state: &GameState) -> [f64; STATE_ENCODING_SIZE] {
+    let mut features = [0.0f64; STATE_ENCODING_SIZE];
+    let mut idx = 0;
+    
+    // Encode grid1: counts per die value per column (6 × 3 = 18 features)
+    for col in 0..3 {
+        let counts = column_value_counts(&state.grid1.column(col));
+        for &count in &counts[1..=6] {
+            features[idx] = count as f64 / 3.0; // Normalize by max possible
+            idx += 1;
+        }
+    }
+    
+    // Encode grid2: same format (18 features)
+    for col in 0..3 {
+        let counts = column_value_counts(&state.grid2.column(col));
+        for &count in &counts[1..=6] {
+            features[idx] = count as f64 / 3.0;
+            idx += 1;
+        }
+    }
+    
+    // Current player (1 feature)
+    features[idx] = if state.current_player == Player::Player1 { 0.0 } else { 1.0 };
+    idx += 1;
+    
+    // Current die one-hot (6 features)
+    if let Some(die) = state.current_die {
+        if (1..=6).contains(&die) {
+            features[idx + (die as usize - 1)] = 1.0;
+        }
+    }
+    
+    features
+}
+
+/// Simple MLP for policy and value prediction
+/// Architecture: input -> hidden (ReLU) -> policy head (softmax) + value head (tanh)
+#[wasm_bindgen]
+pub struct PolicyValueNetwork {
+    // Weights for input -> hidden layer
+    w1: Vec<f64>,  // HIDDEN_SIZE × STATE_ENCODING_SIZE
+    b1: Vec<f64>,  // HIDDEN_SIZE
+    
+    // Weights for hidden -> policy output
+    w_policy: Vec<f64>,  // POLICY_OUTPUT_SIZE × HIDDEN_SIZE
+    b_policy: Vec<f64>,  // POLICY_OUTPUT_SIZE
+    
+    // Weights for hidden -> value output
+    w_value: Vec<f64>,  // 1 × HIDDEN_SIZE
+    b_value: f64,
+    
+    // Whether weights have been loaded
+    initialized: bool,
+}
+
+impl Default for PolicyValueNetwork {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[wasm_bindgen]
+impl PolicyValueNetwork {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> Self {
+        // Initialize with small random weights (Xavier initialization)
+        let scale1 = (2.0 / (STATE_ENCODING_SIZE + HIDDEN_SIZE) as f64).sqrt();
+        let scale_policy = (2.0 / (HIDDEN_SIZE + POLICY_OUTPUT_SIZE) as f64).sqrt();
+        let scale_value = (2.0 / (HIDDEN_SIZE + 1) as f64).sqrt();
+        
+        let mut w1 = vec![0.0; HIDDEN_SIZE * STATE_ENCODING_SIZE];
+        let b1 = vec![0.0; HIDDEN_SIZE];
+        let mut w_policy = vec![0.0; POLICY_OUTPUT_SIZE * HIDDEN_SIZE];
+        let b_policy = vec![0.0; POLICY_OUTPUT_SIZE];
+        let mut w_value = vec![0.0; HIDDEN_SIZE];
+        
+        // Initialize with small random values
+        for w in w1.iter_mut() {
+            *w = (js_sys::Math::random() - 0.5) * 2.0 * scale1;
+        }
+        for w in w_policy.iter_mut() {
+            *w = (js_sys::Math::random() - 0.5) * 2.0 * scale_policy;
+        }
+        for w in w_value.iter_mut() {
+            *w = (js_sys::Math::random() - 0.5) * 2.0 * scale_value;
+        }
+        
+        PolicyValueNetwork {
+            w1,
+            b1,
+            w_policy,
+            b_policy,
+            w_value,
+            b_value: 0.0,
+            initialized: false,
+        }
+    }
+    
+    /// Load weights from a flat array
+    /// Format: [w1..., b1..., w_policy..., b_policy..., w_value..., b_value]
+    #[wasm_bindgen]
+    pub fn load_weights(&mut self, weights: &[f64]) -> bool {
+        let expected_size = 
+            HIDDEN_SIZE * STATE_ENCODING_SIZE +  // w1
+            HIDDEN_SIZE +                         // b1
+            POLICY_OUTPUT_SIZE * HIDDEN_SIZE +    // w_policy
+            POLICY_OUTPUT_SIZE +                  // b_policy
+            HIDDEN_SIZE +                         // w_value
+            1;                                    // b_value
+        
+        if weights.len() != expected_size {
+            return false;
+        }
+        
+        let mut idx = 0;
+        
+        // Load w1
+        for i in 0..self.w1.len() {
+            self.w1[i] = weights[idx];
+            idx += 1;
+        }
+        
+        // Load b1
+        for i in 0..self.b1.len() {
+            self.b1[i] = weights[idx];
+            idx += 1;
+        }
+        
+        // Load w_policy
+        for i in 0..self.w_policy.len() {
+            self.w_policy[i] = weights[idx];
+            idx += 1;
+        }
+        
+        // Load b_policy
+        for i in 0..self.b_policy.len() {
+            self.b_policy[i] = weights[idx];
+            idx += 1;
+        }
+        
+        // Load w_value
+        for i in 0..self.w_value.len() {
+            self.w_value[i] = weights[idx];
+            idx += 1;
+        }
+        
+        // Load b_value
+        self.b_value = weights[idx];
+        
+        self.initialized = true;
+        true
+    }
+    
+    /// Check if network has been initialized with weights
+    #[wasm_bindgen]
+    pub fn is_initialized(&self) -> bool {
+        self.initialized
+    }
+    
+    /// Get the total number of weights in the network
+    #[wasm_bindgen]
+    pub fn get_weight_count(&self) -> usize {
+        HIDDEN_SIZE * STATE_ENCODING_SIZE +
+        HIDDEN_SIZE +
+        POLICY_OUTPUT_SIZE * HIDDEN_SIZE +
+        POLICY_OUTPUT_SIZE +
+        HIDDEN_SIZE +
+        1
+    }
+}
+
+impl PolicyValueNetwork {
+    /// Forward pass: returns (policy, value)
+    /// Policy is a probability distribution over 3 columns
+    /// Value is in range [-1, 1]
+    fn forward(&self, state: &GameState) -> ([f64; 3], f64) {
+        let input = encode_state(state);
+        
+        // Hidden layer with ReLU
+        let mut hidden = [0.0f64; HIDDEN_SIZE];
+        for (i, h) in hidden.iter_mut().enumerate() {
+            let weights = &self.w1[i * STATE_ENCODING_SIZE..(i + 1) * STATE_ENCODING_SIZE];
+            let sum: f64 = weights.iter().zip(input.iter()).map(|(w, x)| w * x).sum();
+            *h = (self.b1[i] + sum).max(0.0); // ReLU
+        }
+        
+        // Policy head with softmax
+        let mut policy_logits = [0.0f64; POLICY_OUTPUT_SIZE];
+        for (i, logit) in policy_logits.iter_mut().enumerate() {
+            let weights = &self.w_policy[i * HIDDEN_SIZE..(i + 1) * HIDDEN_SIZE];
+            let sum: f64 = weights.iter().zip(hidden.iter()).map(|(w, h)| w * h).sum();
+            *logit = self.b_policy[i] + sum;
+        }
+        
+        // Softmax
+        let max_logit = policy_logits.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+        let mut exp_sum = 0.0;
+        let mut policy = [0.0f64; POLICY_OUTPUT_SIZE];
+        for (p, &logit) in policy.iter_mut().zip(policy_logits.iter()) {
+            *p = (logit - max_logit).exp();
+            exp_sum += *p;
+        }
+        for p in policy.iter_mut() {
+            *p /= exp_sum;
+        }
+        
+        // Value head with tanh
+        let value_sum = self.b_value
+            + self.w_value.iter().zip(hidden.iter()).map(|(w, h)| w * h).sum::<f64>();
+        let value = value_sum.tanh();
+        
+        (policy, value)
+    }
+    
+    /// Get policy for legal moves only (renormalized)
+    fn get_policy_for_legal(&self, state: &GameState, legal_cols: &[usize]) -> Vec<(usize, f64)> {
+        let (policy, _) = self.forward(state);
+        
+        // Collect policy for legal moves
+        let mut legal_probs: Vec<(usize, f64)> = legal_cols.iter()
+            .map(|&col| (col, policy[col]))
+            .collect();
+        
+        // Renormalize
+        let sum: f64 = legal_probs.iter().map(|(_, p)| p).sum();
+        if sum > 0.0 {
+            for (_, p) in &mut legal_probs {
+                *p /= sum;
+            }
+        } else {
+            // Uniform if sum is 0
+            let uniform = 1.0 / legal_probs.len() as f64;
+            for (_, p) in &mut legal_probs {
+                *p = uniform;
+            }
+        }
+        
+        legal_probs
+    }
+    
+    /// Get value estimate for a state
+    fn get_value(&self, state: &GameState) -> f64 {
+        let (_, value) = self.forward(state);
+        value
+    }
+}
+
+// ============================================================================
+// MCTS (Monte Carlo Tree Search) with PUCT
+// ============================================================================
+
+/// MCTS Configuration
+const MCTS_C_PUCT: f64 = 1.5;  // Exploration constant for PUCT
+const MCTS_DEFAULT_SIMULATIONS: u32 = 800;  // Default number of simulations
+
+/// MCTS Node representing a game state
+#[derive(Clone)]
+struct MCTSNode {
+    /// Number of times this node has been visited
+    visits: u32,
+    /// Total value accumulated from this node (from the perspective of the player who moved here)
+    total_value: f64,
+    /// Prior probability for this action (from policy network or uniform)
+    prior: f64,
+    /// Children keyed by action (column index 0-2)
+    children: HashMap<usize, MCTSNode>,
+    /// Whether this is a terminal state
+    is_terminal: bool,
+    /// Terminal value if is_terminal
+    terminal_value: f64,
+}
+
+impl MCTSNode {
+    fn new(prior: f64) -> Self {
+        MCTSNode {
+            visits: 0,
+            total_value: 0.0,
+            prior,
+            children: HashMap::new(),
+            is_terminal: false,
+            terminal_value: 0.0,
+        }
+    }
+    
+    /// Get the mean value of this node
+    fn mean_value(&self) -> f64 {
+        if self.visits == 0 {
+            0.0
+        } else {
+            self.total_value / self.visits as f64
+        }
+    }
+    
+    /// PUCT score for child selection
+    fn puct_score(&self, child: &MCTSNode, parent_visits: u32) -> f64 {
+        let q = child.mean_value();
+        let u = MCTS_C_PUCT * child.prior * (parent_visits as f64).sqrt() / (1.0 + child.visits as f64);
+        q + u
+    }
+    
+    /// Select the best child according to PUCT
+    fn select_child(&self) -> Option<usize> {
+        if self.children.is_empty() {
+            return None;
+        }
+        
+        let mut best_action = None;
+        let mut best_score = f64::NEG_INFINITY;
+        
+        for (&action, child) in &self.children {
+            let score = self.puct_score(child, self.visits);
+            if score > best_score {
+                best_score = score;
+                best_action = Some(action);
+            }
+        }
+        
+        best_action
+    }
+    
+    /// Select the best action based on visit counts (for final move selection)
+    fn best_action_by_visits(&self) -> Option<usize> {
+        if self.children.is_empty() {
+            return None;
+        }
+        
+        let mut best_action = None;
+        let mut best_visits = 0;
+        
+        for (&action, child) in &self.children {
+            if child.visits > best_visits {
+                best_visits = child.visits;
+                best_action = Some(action);
+            }
+        }
+        
+        best_action
+    }
+}
+
+/// MCTS Search Context
+struct MCTSContext {
+    /// Root node
+    root: MCTSNode,
+    /// Start time for time budget
+    start_time: f64,
+    /// Time budget in ms
+    time_budget_ms: f64,
+    /// Number of simulations run
+    simulations: u32,
+    /// Faces on the die sampled at chance nodes
+    die_faces: u8,
+}
+
+impl MCTSContext {
+    fn new(time_budget_ms: f64, die_faces: u8) -> Self {
+        MCTSContext {
+            root: MCTSNode::new(1.0),
+            start_time: js_sys::Date::now(),
+            time_budget_ms,
+            simulations: 0,
+            die_faces,
+        }
+    }
+    
+    fn should_stop(&self) -> bool {
+        if self.time_budget_ms <= 0.0 {
+            return self.simulations >= MCTS_DEFAULT_SIMULATIONS;
+        }
+        let elapsed = js_sys::Date::now() - self.start_time;
+        elapsed >= self.time_budget_ms
+    }
+}
+
+/// Get legal columns for a state
+fn get_legal_columns(state: &GameState) -> Vec<usize> {
+    let grid = match state.current_player {
+        Player::Player1 => &state.grid1,
+        Player::Player2 => &state.grid2,
+    };
+    
+    (0..3).filter(|&col| !grid.is_column_full(col)).collect()
+}
+
+/// Get uniform prior for legal actions
+fn get_uniform_prior(num_legal: usize) -> f64 {
+    if num_legal == 0 {
+        0.0
+    } else {
+        1.0 / num_legal as f64
+    }
+}
+
+/// Evaluate a state from a player's perspective (normalized to [-1, 1])
+fn evaluate_normalized(state: &GameState, player: Player) -> f64 {
+    let config = DifficultyConfig {
+        depth: 0,
+        randomness: 0.0,
+        offense_weight: 0.5,
+        defense_weight: 0.5,
+        advanced_eval: true,
+        adversarial: true,
+        time_budget_ms: 0.0,
+        ..DifficultyConfig::default()
+    };
+    
+    let raw_eval = evaluate_advanced(state, player, &config);
+    
+    // Handle terminal states
+    if raw_eval >= 10000.0 {
+        return 1.0;
+    } else if raw_eval <= -10000.0 {
+        return -1.0;
+    }
+    
+    // Normalize to roughly [-1, 1] range
+    // Typical score differences are in range [-200, 200]
+    (raw_eval / 200.0).clamp(-1.0, 1.0)
+}
+
+/// Expand a node by adding children for all legal actions
+fn mcts_expand(node: &mut MCTSNode, state: &GameState) {
+    if state.phase == GamePhase::Ended {
+        return;
+    }
+    
+    let legal_columns = get_legal_columns(state);
+    if legal_columns.is_empty() {
+        return;
+    }
+    
+    let prior = get_uniform_prior(legal_columns.len());
+    
+    for col in legal_columns {
+        node.children.entry(col).or_insert_with(|| MCTSNode::new(prior));
+    }
+}
+
+/// Simulate a game from a state to get a value
+/// Sample a uniform roll of a die with `faces` faces
+fn mcts_sample_die(faces: u8) -> u8 {
+    ((js_sys::Math::random() * faces as f64) as u8 + 1).min(faces)
+}
+
+fn mcts_simulate(state: &GameState, player: Player) -> f64 {
+    // Use evaluation function as a quick rollout replacement
+    evaluate_normalized(state, player)
+}
+
+/// Run one MCTS iteration (selection, expansion, simulation, backpropagation)
+fn mcts_iterate(
+    node: &mut MCTSNode,
+    state: &GameState,
+    root_player: Player,
+    die_faces: u8,
+) -> f64 {
+    node.visits += 1;
+    
+    // Terminal state
+    if state.phase == GamePhase::Ended {
+        let value = evaluate_normalized(state, root_player);
+        node.is_terminal = true;
+        node.terminal_value = value;
+        node.total_value += value;
+        return value;
+    }
+    
+    // Handle chance nodes (rolling phase) - sample a die
+    if state.phase == GamePhase::Rolling {
+        // Sample a random die value
+        let die_value = mcts_sample_die(die_faces);
+        let rolled_state = roll_die(state, die_value);
+        let value = mcts_iterate(node, &rolled_state, root_player, die_faces);
+        node.total_value += value;
+        return value;
+    }
+    
+    // Expand if not expanded
+    if node.children.is_empty() {
+        mcts_expand(node, state);
+        
+        // If still no children, this is a terminal-like state
+        if node.children.is_empty() {
+            let value = evaluate_normalized(state, root_player);
+            node.total_value += value;
+            return value;
+        }
+        
+        // Select one child for rollout
+        if let Some(action) = node.select_child() {
+            if let Some(new_state) = apply_move(state, action) {
+                let value = mcts_simulate(&new_state, root_player);
+                if let Some(child) = node.children.get_mut(&action) {
+                    child.visits += 1;
+                    child.total_value += value;
+                }
+                node.total_value += value;
+                return value;
+            }
+        }
+        
+        let value = evaluate_normalized(state, root_player);
+        node.total_value += value;
+        return value;
+    }
+    
+    // Selection - pick best child via PUCT
+    if let Some(action) = node.select_child() {
+        if let Some(new_state) = apply_move(state, action) {
+            // Recursively iterate
+            if let Some(child) = node.children.get_mut(&action) {
+                let value = mcts_iterate(child, &new_state, root_player, die_faces);
+                node.total_value += value;
+                return value;
+            }
+        }
+    }
+    
+    // Fallback
+    let value = evaluate_normalized(state, root_player);
+    node.total_value += value;
+    value
+}
+
+/// Run MCTS search and return the best action
+fn mcts_search(state: &GameState, time_budget_ms: f64, die_faces: u8) -> Option<usize> {
+    if state.phase != GamePhase::Placing || state.current_die.is_none() {
+        return None;
+    }
+    
+    let player = state.current_player;
+    let legal_columns = get_legal_columns(state);
+    
+    if legal_columns.is_empty() {
+        return None;
+    }
+    
+    if legal_columns.len() == 1 {
+        return Some(legal_columns[0]);
+    }
+    
+    let mut ctx = MCTSContext::new(time_budget_ms, die_faces);
+    
+    // Expand root
+    mcts_expand(&mut ctx.root, state);
+    
+    // Run simulations until time budget is exhausted
+    while !ctx.should_stop() {
+        mcts_iterate(&mut ctx.root, state, player, ctx.die_faces);
+        ctx.simulations += 1;
+    }
+    
+    // Select best action by visit count
+    ctx.root.best_action_by_visits()
+}
+
+// Add MCTS method to AIEngine
+#[wasm_bindgen]
+impl AIEngine {
+    /// Get the best move using MCTS (Monte Carlo Tree Search)
+    #[wasm_bindgen]
+    pub fn get_mcts_move(
+        &mut self,
+        grid1: &[u8],
+        grid2: &[u8],
+        current_player: u8,
+        current_die: u8,
+        time_budget_ms: f64,
+    ) -> i32 {
+        // Convert from JS arrays to GameState
+        let state = state_from_js(grid1, grid2, current_player, current_die);
+        
+        if state.phase != GamePhase::Placing || state.current_die.is_none() {
+            return -1;
+        }
+        
+        match mcts_search(&state, time_budget_ms, self.ctx.die_faces) {
+            Some(col) => col as i32,
+            None => -1,
+        }
+    }
+    
+    /// Get the best move using hybrid approach: MCTS with neural network priors
+    /// For now, uses uniform priors until policy network is trained
+    #[wasm_bindgen]
+    pub fn get_hybrid_move(
+        &mut self,
+        grid1: &[u8],
+        grid2: &[u8],
+        current_player: u8,
+        current_die: u8,
+        time_budget_ms: f64,
+        policy_weights: Option<Vec<f64>>,
+    ) -> i32 {
+        // For now, just use MCTS with uniform priors
+        // Policy weights will be used when we have a trained model
+        let _ = policy_weights;
+        self.get_mcts_move(grid1, grid2, current_player, current_die, time_budget_ms)
+    }
+}
+
+// ============================================================================
+// Neural Network-Guided MCTS
+// ============================================================================
+
+/// Expand a node using neural network policy priors
+fn mcts_expand_with_nn(node: &mut MCTSNode, state: &GameState, network: &PolicyValueNetwork) {
+    if state.phase == GamePhase::Ended {
+        return;
+    }
+    
+    let legal_columns = get_legal_columns(state);
+    if legal_columns.is_empty() {
+        return;
+    }
+    
+    // Get policy priors from network
+    let policy_probs = network.get_policy_for_legal(state, &legal_columns);
+    
+    for (col, prior) in policy_probs {
+        node.children.entry(col).or_insert_with(|| MCTSNode::new(prior));
+    }
+}
+
+/// Run one MCTS iteration with neural network guidance
+fn mcts_iterate_with_nn(
+    node: &mut MCTSNode,
+    state: &GameState,
+    root_player: Player,
+    network: &PolicyValueNetwork,
+    die_faces: u8,
+) -> f64 {
+    node.visits += 1;
+    
+    // Terminal state
+    if state.phase == GamePhase::Ended {
+        let value = evaluate_normalized(state, root_player);
+        node.is_terminal = true;
+        node.terminal_value = value;
+        node.total_value += value;
+        return value;
+    }
+    
+    // Handle chance nodes (rolling phase) - sample a die
+    if state.phase == GamePhase::Rolling {
+        let die_value = mcts_sample_die(die_faces);
+        let rolled_state = roll_die(state, die_value);
+        let value = mcts_iterate_with_nn(node, &rolled_state, root_player, network, die_faces);
+        node.total_value += value;
+        return value;
+    }
+    
+    // Expand if not expanded
+    if node.children.is_empty() {
+        mcts_expand_with_nn(node, state, network);
+        
+        if node.children.is_empty() {
+            let value = network.get_value(state);
+            // Convert to root player's perspective
+            let adjusted_value = if state.current_player == root_player {
+                value
+            } else {
+                -value
+            };
+            node.total_value += adjusted_value;
+            return adjusted_value;
+        }
+        
+        // Use network value instead of rollout
+        let value = network.get_value(state);
+        let adjusted_value = if state.current_player == root_player {
+            value
+        } else {
+            -value
+        };
+        
+        // Still need to propagate to children
+        if let Some(action) = node.select_child() {
+            if let Some(child) = node.children.get_mut(&action) {
+                child.visits += 1;
+                child.total_value += adjusted_value;
+            }
+        }
+        
+        node.total_value += adjusted_value;
+        return adjusted_value;
+    }
+    
+    // Selection - pick best child via PUCT
+    if let Some(action) = node.select_child() {
+        if let Some(new_state) = apply_move(state, action) {
+            if let Some(child) = node.children.get_mut(&action) {
+                let value = mcts_iterate_with_nn(child, &new_state, root_player, network, die_faces);
+                node.total_value += value;
+                return value;
+            }
+        }
+    }
+    
+    // Fallback
+    let value = network.get_value(state);
+    let adjusted_value = if state.current_player == root_player {
+        value
+    } else {
+        -value
+    };
+    node.total_value += adjusted_value;
+    adjusted_value
+}
+
+/// Run neural network-guided MCTS search
+fn mcts_search_with_nn(
+    state: &GameState, 
+    time_budget_ms: f64,
+    network: &PolicyValueNetwork,
+    die_faces: u8,
+) -> Option<usize> {
+    if state.phase != GamePhase::Placing || state.current_die.is_none() {
+        return None;
+    }
+    
+    let player = state.current_player;
+    let legal_columns = get_legal_columns(state);
+    
+    if legal_columns.is_empty() {
+        return None;
+    }
+    
+    if legal_columns.len() == 1 {
+        return Some(legal_columns[0]);
+    }
+    
+    let mut ctx = MCTSContext::new(time_budget_ms, die_faces);
+    
+    // Expand root with network priors
+    mcts_expand_with_nn(&mut ctx.root, state, network);
+    
+    // Run simulations
+    while !ctx.should_stop() {
+        mcts_iterate_with_nn(&mut ctx.root, state, player, network, ctx.die_faces);
+        ctx.simulations += 1;
+    }
+    
+    ctx.root.best_action_by_visits()
+}
+
+/// Hybrid AI Engine that combines MCTS with neural network
+#[wasm_bindgen]
+pub struct HybridAIEngine {
+    network: PolicyValueNetwork,
+    die_faces: u8,
+}
+
+impl Default for HybridAIEngine {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[wasm_bindgen]
+impl HybridAIEngine {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> Self {
+        HybridAIEngine {
+            network: PolicyValueNetwork::new(),
+            die_faces: DEFAULT_DIE_FACES,
+        }
+    }
+    
+    /// Set the number of faces on the die sampled during search.
+    /// Returns false and keeps the current setting if `faces` is outside 2..=12.
+    #[wasm_bindgen]
+    pub fn set_die_faces(&mut self, faces: u8) -> bool {
+        if !(2..=MAX_DIE_FACES).contains(&faces) {
+            return false;
+        }
+        self.die_faces = faces;
+        true
+    }
+    
+    /// Load weights into the neural network
+    #[wasm_bindgen]
+    pub fn load_weights(&mut self, weights: &[f64]) -> bool {
+        self.network.load_weights(weights)
+    }
+    
+    /// Check if the network is initialized
+    #[wasm_bindgen]
+    pub fn is_network_initialized(&self) -> bool {
+        self.network.is_initialized()
+    }
+    
+    /// Get the weight count for the network
+    #[wasm_bindgen]
+    pub fn get_weight_count(&self) -> usize {
+        self.network.get_weight_count()
+    }
+    
+    /// Get the best move using neural network-guided MCTS
+    #[wasm_bindgen]
+    pub fn get_move(
+        &self,
+        grid1: &[u8],
+        grid2: &[u8],
+        current_player: u8,
+        current_die: u8,
+        time_budget_ms: f64,
+    ) -> i32 {
+        let state = state_from_js(grid1, grid2, current_player, current_die);
+        
+        if state.phase != GamePhase::Placing || state.current_die.is_none() {
+            return -1;
+        }
+        
+        match mcts_search_with_nn(&state, time_budget_ms, &self.network, self.die_faces) {
+            Some(col) => col as i32,
+            None => -1,
         }
-        let len2 = grid2.len().min(9);
-        for i in 0..len2 {
-            state.grid2.data[i] = grid2[i];
+    }
+    
+    /// Get policy and value outputs for a state (for debugging/analysis)
+    #[wasm_bindgen]
+    pub fn get_policy_value(
+        &self,
+        grid1: &[u8],
+        grid2: &[u8],
+        current_player: u8,
+        current_die: u8,
+    ) -> Vec<f64> {
+        let state = state_from_js(grid1, grid2, current_player, current_die);
+        
+        let (policy, value) = self.network.forward(&state);
+        
+        // Return [policy[0], policy[1], policy[2], value]
+        vec![policy[0], policy[1], policy[2], value]
+    }
+}
+
+// ============================================================================
+// Position Analysis
+// ============================================================================
+
+/// Balanced, deterministic config used by the analysis helpers (depth floors at 1)
+fn analysis_config(depth: u32) -> DifficultyConfig {
+    DifficultyConfig {
+        depth: depth.max(1),
+        randomness: 0.0,
+        offense_weight: 0.5,
+        defense_weight: 0.5,
+        advanced_eval: true,
+        adversarial: false,
+        time_budget_ms: 0.0,
+        ..DifficultyConfig::default()
+    }
+}
+
+/// Value of a position for the side to move under `analysis_config(depth)`,
+/// averaging over the roll when no die is set. A board with a full grid is
+/// scored as finished.
+fn analysis_value(mut state: GameState, depth: u32, ctx: &mut SearchContext) -> f64 {
+    let to_move = state.current_player;
+    let config = analysis_config(depth);
+    if state.grid1.is_full() || state.grid2.is_full() {
+        state.phase = GamePhase::Ended;
+        evaluate(&state, to_move, &config)
+    } else {
+        ctx.begin_fixed_search(false);
+        chance_node(&state, config.depth, to_move, &config, &config, ctx)
+    }
+}
+
+/// Search depth used for tutorial explanations
+const TUTORIAL_DEPTH: u32 = 3;
+
+/// Search depth for the move values along a `line_sharpness` line
+const SHARPNESS_DEPTH: u32 = 2;
+
+/// Longest line `line_sharpness` will follow
+const MAX_SHARPNESS_PLIES: u32 = 18;
+
+/// Human-readable column names for generated explanations
+const COLUMN_NAMES: [&str; 3] = ["left", "middle", "right"];
+
+// Game transcripts are flat byte arrays: the first byte is the player who
+// moved first (0 or 1), followed by one (die, column) pair per placement,
+// starting from an empty board with players alternating.
+
+/// Replay a transcript from the empty board.
+/// Returns the placing-phase state before each move together with the column
+/// played, or None if the transcript is malformed or contains an illegal move.
+fn replay_transcript(transcript: &[u8], die_faces: u8) -> Option<Vec<(GameState, usize)>> {
+    let (&first, moves) = transcript.split_first()?;
+    if first > 1 {
+        return None;
+    }
+    replay_from(state_from_js(&[], &[], first, 0), moves, die_faces).map(|(plies, _)| plies)
+}
+
+/// Apply (die, column) pairs to a position waiting on a roll. Returns the
+/// placing-phase state before each move with the column played, plus the final
+/// position, or None if the list is malformed or any move is illegal.
+fn replay_from(
+    mut state: GameState,
+    moves: &[u8],
+    die_faces: u8,
+) -> Option<(Vec<(GameState, usize)>, GameState)> {
+    if !moves.len().is_multiple_of(2) {
+        return None;
+    }
+    let mut plies = Vec::with_capacity(moves.len() / 2);
+    for pair in moves.chunks(2) {
+        let (die_value, col) = (pair[0], pair[1] as usize);
+        if state.phase != GamePhase::Rolling || die_value == 0 || die_value > die_faces || col > 2 {
+            return None;
         }
-        for i in len2..9 {
-            state.grid2.data[i] = 0;
+        let rolled = roll_die(&state, die_value);
+        state = apply_move(&rolled, col)?;
+        plies.push((rolled, col));
+    }
+    Some((plies, state))
+}
+
+/// Longest horizon (in plies) a column projection will look ahead
+const MAX_PROJECTION_PLIES: u32 = 18;
+
+/// Expected final score of one column after `plies` more half-moves.
+///
+/// Assumptions:
+/// - Every roll is uniform over 1..=faces.
+/// - On our turns we place the roll in the column only when that raises the
+///   projected value; otherwise we play it elsewhere (other columns are assumed
+///   to have room).
+/// - On the opponent's turns they capture whenever their roll matches one of
+///   our dice and their mirrored column still has space, filling one of their
+///   slots each time. Non-matching rolls are played elsewhere.
+fn project_column(
+    column: [u8; 3],
+    opp_free: usize,
+    self_to_move: bool,
+    plies: u32,
+    faces: u8,
+    memo: &mut HashMap<([u8; 3], usize, bool, u32), f64>,
+) -> f64 {
+    if plies == 0 {
+        return calculate_column_score(&column) as f64;
+    }
+
+    let mut key_column = column;
+    key_column.sort_unstable();
+    let key = (key_column, opp_free, self_to_move, plies);
+    if let Some(&value) = memo.get(&key) {
+        return value;
+    }
+
+    let mut total = 0.0;
+    for die_value in 1..=faces {
+        let value = if self_to_move {
+            let skip = project_column(column, opp_free, false, plies - 1, faces, memo);
+            if let Some(slot) = column.iter().position(|&v| v == 0) {
+                let mut placed = column;
+                placed[slot] = die_value;
+                skip.max(project_column(placed, opp_free, false, plies - 1, faces, memo))
+            } else {
+                skip
+            }
+        } else if opp_free > 0 && column.contains(&die_value) {
+            let mut remaining = [0u8; 3];
+            for (idx, &v) in column.iter().filter(|&&v| v != 0 && v != die_value).enumerate() {
+                remaining[idx] = v;
+            }
+            project_column(remaining, opp_free - 1, true, plies - 1, faces, memo)
+        } else {
+            project_column(column, opp_free, true, plies - 1, faces, memo)
+        };
+        total += value / faces as f64;
+    }
+
+    memo.insert(key, total);
+    total
+}
+
+/// The opponent's most damaging immediate reply once they roll `die_value`.
+///
+/// `state` is the position after our placement with the opponent to roll.
+/// Returns the reply column and the resulting score margin from `player`'s
+/// perspective, or None if the opponent has no legal placement.
+fn best_opponent_reply(state: &GameState, die_value: u8, player: Player) -> Option<(usize, f64)> {
+    let rolled = roll_die(state, die_value);
+    let mut best: Option<(usize, f64)> = None;
+    for col in get_legal_columns(&rolled) {
+        if let Some(new_state) = apply_move(&rolled, col) {
+            let margin = evaluate_basic(&new_state, player);
+            if best.is_none_or(|(_, m)| margin < m) {
+                best = Some((col, margin));
+            }
         }
+    }
+    best
+}
+
+/// Opponent models used by `opponent_robustness`: greedy, medium and expert strength
+fn robustness_opponent_models() -> [DifficultyConfig; 3] {
+    [
+        DifficultyConfig::default(),
+        analysis_config(3),
+        DifficultyConfig { adversarial: true, ..analysis_config(6) },
+    ]
+}
+
+/// Search the root once per opponent model and pick the move with the best
+/// worst-case value across them. Returns the robust column and whether the
+/// models disagreed on the best move.
+fn robust_root_choice(
+    state: &GameState,
+    player_config: &DifficultyConfig,
+    ctx: &mut SearchContext,
+) -> Option<(usize, bool)> {
+    let player = state.current_player;
+    let mut worst_case: Vec<(usize, f64)> = Vec::new();
+    let mut model_bests: Vec<usize> = Vec::new();
+    
+    for opponent_config in robustness_opponent_models() {
+        // Cached values depend on the opponent model, so each search starts fresh
+        ctx.begin_fixed_search(opponent_config.adversarial);
+        let values = evaluate_root_moves(state, player, player_config, &opponent_config, ctx);
         
-        if state.phase != GamePhase::Placing || state.current_die.is_none() {
+        if let Some(&(best_col, _)) = values.iter()
+            .max_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal))
+        {
+            model_bests.push(best_col);
+        }
+        if worst_case.is_empty() {
+            worst_case = values;
+        } else {
+            for (entry, &(_, value)) in worst_case.iter_mut().zip(values.iter()) {
+                entry.1 = entry.1.min(value);
+            }
+        }
+    }
+    ctx.clear();
+    
+    let disagree = model_bests.windows(2).any(|pair| pair[0] != pair[1]);
+    worst_case.iter()
+        .max_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal))
+        .map(|&(col, _)| (col, disagree))
+}
+
+/// Append the 20-byte position record used by `successors` and
+/// `simulate_sequence`: both grids, the side to move (0/1) and the phase
+/// (0 = rolling, 1 = placing, 2 = ended)
+fn encode_position(state: &GameState, out: &mut Vec<u8>) {
+    out.extend_from_slice(&state.grid1.data);
+    out.extend_from_slice(&state.grid2.data);
+    out.push(if state.current_player == Player::Player1 { 0 } else { 1 });
+    out.push(match state.phase {
+        GamePhase::Rolling => 0,
+        GamePhase::Placing => 1,
+        GamePhase::Ended => 2,
+    });
+}
+
+/// Record kinds emitted by `diff_grids`
+const DIFF_PLACED: u8 = 0;
+const DIFF_REMOVED: u8 = 1;
+const DIFF_SHIFTED: u8 = 2;
+
+/// Diff one column, appending `[kind, cell, value_or_source]` records.
+/// Dice keep their relative order when a removal compacts a column, so the
+/// longest common subsequence of the two columns' dice are the survivors; the
+/// rest were removed (before) or placed (after).
+fn diff_column(col: usize, before: [u8; 3], after: [u8; 3], out: &mut Vec<u8>) {
+    let old: Vec<(usize, u8)> = before.iter().copied().enumerate().filter(|&(_, v)| v != 0).collect();
+    let new: Vec<(usize, u8)> = after.iter().copied().enumerate().filter(|&(_, v)| v != 0).collect();
+    
+    // lcs[i][j] = longest common subsequence of old[i..] and new[j..]
+    let mut lcs = [[0u8; 4]; 4];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lcs[i][j] = if old[i].1 == new[j].1 {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+    
+    let cell = |row: usize| (col * 3 + row) as u8;
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old[i].1 == new[j].1 {
+            if old[i].0 != new[j].0 {
+                out.extend_from_slice(&[DIFF_SHIFTED, cell(new[j].0), cell(old[i].0)]);
+            }
+            i += 1;
+            j += 1;
+        } else if j == new.len() || (i < old.len() && lcs[i + 1][j] >= lcs[i][j + 1]) {
+            out.extend_from_slice(&[DIFF_REMOVED, cell(old[i].0), old[i].1]);
+            i += 1;
+        } else {
+            out.extend_from_slice(&[DIFF_PLACED, cell(new[j].0), new[j].1]);
+            j += 1;
+        }
+    }
+}
+
+/// Best placement plan for a fixed sequence of rolls with no opponent moves in
+/// between. `state` is the rolling-phase position of `player`. Returns the final
+/// score margin and the column for each roll used; rolls left over once the
+/// player's grid fills are ignored. Ties keep the lowest columns.
+fn solve_solitaire_sequence(state: &GameState, player: Player, rolls: &[u8]) -> (f64, Vec<u8>) {
+    let (&die_value, rest) = match rolls.split_first() {
+        Some(split) => split,
+        None => return (evaluate_basic(state, player), Vec::new()),
+    };
+    
+    let rolled = roll_die(state, die_value);
+    let mut best: Option<(f64, Vec<u8>)> = None;
+    for col in get_legal_columns(&rolled) {
+        let mut next = match apply_move(&rolled, col) {
+            Some(next) => next,
+            None => continue,
+        };
+        let (value, mut plan) = if next.phase == GamePhase::Ended {
+            (evaluate_basic(&next, player), Vec::new())
+        } else {
+            // Solitaire: the turn comes straight back to the player
+            next.current_player = player;
+            solve_solitaire_sequence(&next, player, rest)
+        };
+        if best.as_ref().is_none_or(|(best_value, _)| value > *best_value) {
+            plan.insert(0, col as u8);
+            best = Some((value, plan));
+        }
+    }
+    best.unwrap_or_else(|| (evaluate_basic(state, player), Vec::new()))
+}
+
+/// Problems reported by `audit_position`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[wasm_bindgen]
+pub enum PositionProblem {
+    /// A grid does not have exactly 9 cells
+    GridLength = 0,
+    /// A cell holds a value above the die's face count
+    InvalidCellValue = 1,
+    /// A die sits above an empty cell in its column
+    GravityViolation = 2,
+    /// The current die is above the face count
+    DieOutOfRange = 3,
+    /// The current player is neither 0 nor 1
+    InvalidPlayer = 4,
+    /// A die is waiting to be placed although a grid is full (game over)
+    DieAfterGameEnd = 5,
+    /// More dice are on the board than turns have been played
+    TurnNumberMismatch = 6,
+}
+
+/// Deepest forced-win search (in placements) `all_winning_moves` will attempt
+const FORCED_WIN_MAX_PLIES: u32 = 8;
+
+/// Whether `player` wins from `state` (rolling phase or finished) against every
+/// roll and every opponent reply within `plies` more placements
+fn forces_win(
+    state: &GameState,
+    player: Player,
+    plies: u32,
+    faces: u8,
+    memo: &mut HashMap<(GameState, u32), bool>,
+) -> bool {
+    if state.phase == GamePhase::Ended {
+        return evaluate_basic(state, player) > 0.0;
+    }
+    if plies == 0 {
+        return false;
+    }
+    if let Some(&known) = memo.get(&(state.clone(), plies)) {
+        return known;
+    }
+    
+    let wins = (1..=faces).all(|die_value| {
+        let rolled = roll_die(state, die_value);
+        let mut outcomes = get_legal_columns(&rolled).into_iter()
+            .filter_map(|col| apply_move(&rolled, col))
+            .map(|next| forces_win(&next, player, plies - 1, faces, memo));
+        if rolled.current_player == player {
+            outcomes.any(|win| win)
+        } else {
+            outcomes.all(|win| win)
+        }
+    });
+    memo.insert((state.clone(), plies), wins);
+    wins
+}
+
+#[wasm_bindgen]
+impl AIEngine {
+    /// Project the expected points gained in `col` over the next `plies` half-moves.
+    ///
+    /// `player_to_move` is 0 if the owner of `grid_self` moves first and 1 if the
+    /// opponent does. See `project_column` for the roll and opponent model.
+    /// Returns 0.0 for an invalid column.
+    #[wasm_bindgen]
+    pub fn column_value_projection(
+        &self,
+        grid_self: &[u8],
+        grid_opp: &[u8],
+        player_to_move: u8,
+        col: u8,
+        plies: u32,
+    ) -> f64 {
+        if col > 2 {
+            return 0.0;
+        }
+        let col = col as usize;
+        let my_grid = Grid::from_slice(grid_self);
+        let opp_grid = Grid::from_slice(grid_opp);
+
+        let column = my_grid.column(col);
+        let opp_free = opp_grid.column(col).iter().filter(|&&v| v == 0).count();
+        let mut memo = HashMap::new();
+        let projected = project_column(
+            column,
+            opp_free,
+            player_to_move == 0,
+            plies.min(MAX_PROJECTION_PLIES),
+            self.ctx.die_faces,
+            &mut memo,
+        );
+
+        projected - calculate_column_score(&column) as f64
+    }
+    
+    /// Get the move whose worst-case outcome after the opponent's reply is best.
+    ///
+    /// A one-ply maximin: each placement is scored by the lowest score margin the
+    /// opponent can leave us with over every roll and reply, and the placement
+    /// with the highest such floor is chosen. Returns -1 if there is no move.
+    #[wasm_bindgen]
+    pub fn maximin_move(
+        &self,
+        grid1: &[u8],
+        grid2: &[u8],
+        current_player: u8,
+        current_die: u8,
+    ) -> i32 {
+        if !dice_in_range(grid1, grid2, current_die, self.ctx.die_faces) {
             return -1;
         }
-        
+
+        let state = state_from_js(grid1, grid2, current_player, current_die);
+        if state.phase != GamePhase::Placing {
+            return -1;
+        }
+
         let player = state.current_player;
-        let grid = match player {
-            Player::Player1 => &state.grid1,
-            Player::Player2 => &state.grid2,
+        let mut best_move: i32 = -1;
+        let mut best_floor = f64::NEG_INFINITY;
+
+        for col in get_legal_columns(&state) {
+            let new_state = match apply_move(&state, col) {
+                Some(new_state) => new_state,
+                None => continue,
+            };
+
+            let floor = if new_state.phase == GamePhase::Ended {
+                evaluate_basic(&new_state, player)
+            } else {
+                (1..=self.ctx.die_faces)
+                    .filter_map(|die_value| best_opponent_reply(&new_state, die_value, player))
+                    .map(|(_, margin)| margin)
+                    .fold(f64::INFINITY, f64::min)
+            };
+
+            if floor > best_floor {
+                best_floor = floor;
+                best_move = col as i32;
+            }
+        }
+
+        best_move
+    }
+    
+    /// Find the player's most costly move in a completed game transcript.
+    ///
+    /// Every move made by `player` is graded by searching the position before it
+    /// (with the opponent modeled by the same config) and measuring how far the
+    /// played column fell short of the best one. Returns the ply index (0-based,
+    /// counting both players' moves) of the largest drop, or -1 if the transcript
+    /// is invalid or none of the player's moves lost value.
+    #[wasm_bindgen]
+    pub fn find_key_mistake(
+        &mut self,
+        replay: &[u8],
+        player: u8,
+        depth: u32,
+        offense_weight: f64,
+        defense_weight: f64,
+        advanced_eval: bool,
+    ) -> i32 {
+        let plies = match replay_transcript(replay, self.ctx.die_faces) {
+            Some(plies) => plies,
+            None => return -1,
+        };
+        let player = if player == 0 { Player::Player1 } else { Player::Player2 };
+        let config = DifficultyConfig {
+            depth: depth.max(1),
+            randomness: 0.0,
+            offense_weight,
+            defense_weight,
+            advanced_eval,
+            adversarial: false,
+            time_budget_ms: 0.0,
+            ..DifficultyConfig::default()
         };
         
-        let legal_columns: Vec<usize> = (0..3)
-            .filter(|&col| !grid.is_column_full(col))
-            .collect();
+        self.ctx.begin_fixed_search(false);
         
-        if legal_columns.is_empty() {
-            return -1;
+        let mut worst_ply: i32 = -1;
+        let mut worst_drop = 0.0;
+        for (ply, (state, played)) in plies.iter().enumerate() {
+            if state.current_player != player {
+                continue;
+            }
+            let values = evaluate_root_moves(state, player, &config, &config, &mut self.ctx);
+            let best = values.iter().map(|&(_, v)| v).fold(f64::NEG_INFINITY, f64::max);
+            let played_value = values.iter().find(|&&(col, _)| col == *played).map(|&(_, v)| v);
+            if let Some(played_value) = played_value {
+                let drop = best - played_value;
+                if drop > worst_drop {
+                    worst_drop = drop;
+                    worst_ply = ply as i32;
+                }
+            }
         }
         
-        if legal_columns.len() == 1 {
-            return legal_columns[0] as i32;
+        worst_ply
+    }
+    
+    /// Get a stable key for the two grids and the side to move, ignoring the die.
+    /// Suited to opening books and statistics that aggregate a position across
+    /// different rolls. Returns 0 if a cell is larger than the configured die.
+    #[wasm_bindgen]
+    pub fn board_key(&self, grid1: &[u8], grid2: &[u8], current_player: u8) -> u64 {
+        if !dice_in_range(grid1, grid2, 0, self.ctx.die_faces) {
+            return 0;
+        }
+        zobrist_board(&state_from_js(grid1, grid2, current_player, 0))
+    }
+    
+    /// Search the opponent's best plan over the next `depth` turns and return how
+    /// many points of score margin they can expect to gain from here.
+    ///
+    /// Roles are swapped relative to the normal search: the opponent (`opponent`
+    /// is 0 for Player1, 1 for Player2) is the maximizer about to roll, and the
+    /// owner of `grid_self` replies adversarially. Uses the basic evaluation so
+    /// the result is in raw score points.
+    #[wasm_bindgen]
+    pub fn opponent_threat_search(
+        &mut self,
+        grid_self: &[u8],
+        grid_opp: &[u8],
+        opponent: u8,
+        depth: u32,
+    ) -> f64 {
+        if !dice_in_range(grid_self, grid_opp, 0, self.ctx.die_faces) {
+            return 0.0;
         }
         
-        // Random move based on difficulty
-        if randomness > 0.0 && js_sys::Math::random() < randomness {
-            let idx = (js_sys::Math::random() * legal_columns.len() as f64) as usize;
-            return legal_columns[idx] as i32;
+        let (grid1, grid2) = if opponent == 0 { (grid_opp, grid_self) } else { (grid_self, grid_opp) };
+        let state = state_from_js(grid1, grid2, opponent, 0);
+        let threat_player = state.current_player;
+        let config = DifficultyConfig {
+            advanced_eval: false,
+            adversarial: true,
+            ..analysis_config(depth)
+        };
+        
+        self.ctx.begin_fixed_search(true);
+        
+        let baseline = evaluate_basic(&state, threat_player);
+        let projected = chance_node(&state, config.depth, threat_player, &config, &config, &mut self.ctx);
+        projected - baseline
+    }
+    
+    /// Get each column's equity: its backed-up expectimax value minus the mean
+    /// value of all legal columns, as `[col0, col1, col2]`.
+    ///
+    /// Positive equity means the column beats an average legal move, so the best
+    /// column always has the highest equity. Full columns are reported as NaN.
+    /// With a single legal column its equity is 0. Returns an empty vec if the
+    /// position is not a valid placing position.
+    #[wasm_bindgen]
+    pub fn column_equity(
+        &mut self,
+        grid1: &[u8],
+        grid2: &[u8],
+        current_player: u8,
+        current_die: u8,
+        depth: u32,
+    ) -> Vec<f64> {
+        let state = match placing_state_from_js(grid1, grid2, current_player, current_die, self.ctx.die_faces) {
+            Ok(state) => state,
+            Err(_) => return Vec::new(),
+        };
+        let config = analysis_config(depth);
+        self.ctx.begin_fixed_search(false);
+        
+        let values = evaluate_root_moves(&state, state.current_player, &config, &config, &mut self.ctx);
+        let mean = values.iter().map(|&(_, v)| v).sum::<f64>() / values.len() as f64;
+        
+        let mut equity = vec![f64::NAN; 3];
+        for (col, value) in values {
+            equity[col] = value - mean;
         }
+        equity
+    }
+    
+    /// Analyze a hand-crafted position for tutorials, returning JSON.
+    ///
+    /// On success: `{"valid":true,"best_column":c,"columns":[...],"rationale":"..."}`
+    /// where each column entry has `column`, `legal`, and for legal columns the
+    /// backed-up `value`, the immediate `score_gain`, the `opponent_loss` from
+    /// captures and the resulting `my_score`/`opponent_score`. Grids that break
+    /// gravity or hold out-of-range dice yield `{"valid":false,"error":"..."}`.
+    #[wasm_bindgen]
+    pub fn tutorial_analysis(
+        &mut self,
+        grid1: &[u8],
+        grid2: &[u8],
+        current_player: u8,
+        current_die: u8,
+    ) -> String {
+        let state = match placing_state_from_js(grid1, grid2, current_player, current_die, self.ctx.die_faces) {
+            Ok(state) if state.grid1.respects_gravity() && state.grid2.respects_gravity() => state,
+            Ok(_) => return String::from("{\"valid\":false,\"error\":\"InvalidInput\"}"),
+            Err(status) => return format!("{{\"valid\":false,\"error\":\"{:?}\"}}", status),
+        };
         
-        // Greedy (depth 0)
-        if depth == 0 {
-            let die_value = state.current_die.unwrap();
-            let mut best_col = legal_columns[0];
-            let mut best_score = f64::NEG_INFINITY;
-            for &col in &legal_columns {
-                let score = evaluate_move_quick(&state, col, die_value, player);
-                if score > best_score {
-                    best_score = score;
-                    best_col = col;
+        let player = state.current_player;
+        let die_value = current_die;
+        let config = analysis_config(TUTORIAL_DEPTH);
+        self.ctx.begin_fixed_search(false);
+        let values = evaluate_root_moves(&state, player, &config, &config, &mut self.ctx);
+        
+        let (best_col, _) = values.iter()
+            .copied()
+            .fold((values[0].0, f64::NEG_INFINITY), |best, (col, v)| if v > best.1 { (col, v) } else { best });
+        
+        let (my_grid, opp_grid) = match player {
+            Player::Player1 => (&state.grid1, &state.grid2),
+            Player::Player2 => (&state.grid2, &state.grid1),
+        };
+        
+        let mut columns = Vec::with_capacity(3);
+        for col in 0..3 {
+            let entry = match values.iter().find(|&&(c, _)| c == col) {
+                Some(&(_, value)) => {
+                    let after = apply_move(&state, col).unwrap_or_else(|| state.clone());
+                    let (my_after, opp_after) = match player {
+                        Player::Player1 => (&after.grid1, &after.grid2),
+                        Player::Player2 => (&after.grid2, &after.grid1),
+                    };
+                    format!(
+                        "{{\"column\":{},\"legal\":true,\"value\":{:.2},\"score_gain\":{},\"opponent_loss\":{},\"my_score\":{},\"opponent_score\":{}}}",
+                        col,
+                        value,
+                        calculate_move_score_gain(my_grid, col, die_value),
+                        calculate_opponent_score_loss(opp_grid, col, die_value),
+                        calculate_grid_score(my_after),
+                        calculate_grid_score(opp_after),
+                    )
                 }
-            }
-            return best_col as i32;
+                None => format!("{{\"column\":{},\"legal\":false}}", col),
+            };
+            columns.push(entry);
         }
         
-        // Setup configs
-        let player_config = DifficultyConfig {
-            depth,
-            randomness,
-            offense_weight,
-            defense_weight,
-            advanced_eval,
-            adversarial,
-            time_budget_ms,
+        let gain = calculate_move_score_gain(my_grid, best_col, die_value);
+        let loss = calculate_opponent_score_loss(opp_grid, best_col, die_value);
+        let rationale = if values.len() == 1 {
+            format!("The {} column is the only legal placement.", COLUMN_NAMES[best_col])
+        } else if loss > 0 {
+            format!(
+                "Placing the {} in the {} column removes {} points from your opponent and gains you {}.",
+                die_value, COLUMN_NAMES[best_col], loss, gain
+            )
+        } else if gain > die_value as i32 {
+            format!(
+                "Placing the {} in the {} column builds a combo worth {} points.",
+                die_value, COLUMN_NAMES[best_col], gain
+            )
+        } else {
+            format!(
+                "The {} column gives the best long-term value for the {}, keeping your other columns flexible.",
+                COLUMN_NAMES[best_col], die_value
+            )
         };
         
-        let opponent_config = DifficultyConfig {
-            depth: opponent_depth,
-            randomness: opponent_randomness,
-            offense_weight: opponent_offense_weight,
-            defense_weight: opponent_defense_weight,
-            advanced_eval: opponent_advanced_eval,
-            adversarial: opponent_adversarial,
-            time_budget_ms: opponent_time_budget_ms,
+        format!(
+            "{{\"valid\":true,\"best_column\":{},\"columns\":[{}],\"rationale\":\"{}\"}}",
+            best_col,
+            columns.join(","),
+            rationale
+        )
+    }
+    
+    /// Get the move that holds up best whether the opponent is greedy, medium or
+    /// expert strength: the column with the highest worst-case value across the
+    /// three opponent models. Returns -1 for invalid input or when no move is possible.
+    #[wasm_bindgen]
+    pub fn opponent_robustness(
+        &mut self,
+        grid1: &[u8],
+        grid2: &[u8],
+        current_player: u8,
+        current_die: u8,
+        player_depth: u32,
+    ) -> i32 {
+        let state = match placing_state_from_js(grid1, grid2, current_player, current_die, self.ctx.die_faces) {
+            Ok(state) => state,
+            Err(_) => return -1,
         };
+        robust_root_choice(&state, &analysis_config(player_depth), &mut self.ctx)
+            .map_or(-1, |(col, _)| col as i32)
+    }
+    
+    /// Whether the best move changes with the assumed opponent strength (greedy,
+    /// medium or expert). When true, `opponent_robustness` gives the hedging move.
+    #[wasm_bindgen]
+    pub fn opponent_models_disagree(
+        &mut self,
+        grid1: &[u8],
+        grid2: &[u8],
+        current_player: u8,
+        current_die: u8,
+        player_depth: u32,
+    ) -> bool {
+        let state = match placing_state_from_js(grid1, grid2, current_player, current_die, self.ctx.die_faces) {
+            Ok(state) => state,
+            Err(_) => return false,
+        };
+        robust_root_choice(&state, &analysis_config(player_depth), &mut self.ctx)
+            .is_some_and(|(_, disagree)| disagree)
+    }
+    
+    /// Describe how one grid changed between two positions, for animating
+    /// transitions and checking replays. Returns flat `[kind, cell, x]` records
+    /// (cells are `col * 3 + row`):
+    /// - kind 0 (placed): a die of value `x` appeared in `cell`
+    /// - kind 1 (removed): the die of value `x` in `cell` was removed
+    /// - kind 2 (shifted): the die now in `cell` slid there from cell `x`
+    ///   after a removal below it
+    ///
+    /// Returns an empty array for identical grids or inputs longer than 9 cells.
+    #[wasm_bindgen]
+    pub fn diff_grids(&self, before: &[u8], after: &[u8]) -> Vec<u8> {
+        if before.len() > 9 || after.len() > 9 {
+            return Vec::new();
+        }
+        let (before, after) = (Grid::from_slice(before), Grid::from_slice(after));
+        let mut out = Vec::new();
+        for col in 0..3 {
+            diff_column(col, before.column(col), after.column(col), &mut out);
+        }
+        out
+    }
+    
+    /// Current score margin next to the expected margin after a `depth`-ply search,
+    /// as `[current_margin, expected_margin]` from `player`'s perspective, with
+    /// `player` to move. Pass `current_die` 0 to average over the coming roll.
+    /// The projection uses the basic (score-only) evaluation, so both numbers are
+    /// in points; on a finished board they are equal. Returns an empty vec for
+    /// invalid input.
+    #[wasm_bindgen]
+    pub fn standing_vs_projection(
+        &mut self,
+        grid1: &[u8],
+        grid2: &[u8],
+        player: u8,
+        current_die: u8,
+        depth: u32,
+    ) -> Vec<f64> {
+        if player > 1 || grid1.len() > 9 || grid2.len() > 9
+            || !dice_in_range(grid1, grid2, current_die, self.ctx.die_faces)
+        {
+            return Vec::new();
+        }
+        let state = state_from_js(grid1, grid2, player, current_die);
+        let perspective = state.current_player;
+        let current = evaluate_basic(&state, perspective);
+        if state.grid1.is_full() || state.grid2.is_full() {
+            return vec![current, current];
+        }
         
-        // Setup context
-        self.ctx.use_adversarial = adversarial;
-        self.ctx.start_time = js_sys::Date::now();
-        self.ctx.time_budget_ms = time_budget_ms;
-        self.ctx.aborted = false;
-        
-        // Use iterative deepening if time budget is set
-        let best_move = if time_budget_ms > 0.0 {
-            let (move_opt, _, _) = iterative_deepening(&state, player, &player_config, &opponent_config, &mut self.ctx);
-            move_opt
-        } else {
-            let (move_opt, _) = expectimax_internal(&state, player, &player_config, &opponent_config, &mut self.ctx);
-            move_opt
+        let config = DifficultyConfig {
+            advanced_eval: false,
+            ..analysis_config(depth)
         };
+        self.ctx.begin_fixed_search(false);
+        let expected = chance_node(&state, config.depth, perspective, &config, &config, &mut self.ctx);
+        vec![current, expected]
+    }
+    
+    /// Backed-up value of a position as Player1's advantage: positive favors
+    /// Player1 whichever side is to move. The search runs for the side to move
+    /// (averaging over the roll when `current_die` is 0) and the value is negated
+    /// when that is Player2. Returns NaN for invalid input.
+    #[wasm_bindgen]
+    pub fn absolute_advantage(
+        &mut self,
+        grid1: &[u8],
+        grid2: &[u8],
+        current_player: u8,
+        current_die: u8,
+        depth: u32,
+    ) -> f64 {
+        if current_player > 1 || grid1.len() > 9 || grid2.len() > 9
+            || !dice_in_range(grid1, grid2, current_die, self.ctx.die_faces)
+        {
+            return f64::NAN;
+        }
+        let state = state_from_js(grid1, grid2, current_player, current_die);
+        let to_move = state.current_player;
+        let value = analysis_value(state, depth, &mut self.ctx);
         
-        match best_move {
-            Some(col) => col as i32,
-            None => legal_columns[0] as i32,
+        match to_move {
+            Player::Player1 => value,
+            Player::Player2 => -value,
         }
     }
     
-    /// Get the best move using Master AI with adaptive weights from opponent profile
+    /// How much the evaluation changes for `current_player` if one die were
+    /// taken off the board: the `depth`-ply value (averaged over the coming
+    /// roll) with the cell at (`col`, `row`) of `target_grid` (0 or 1) cleared
+    /// and its column compacted, minus the value of the position as it is.
+    /// Positive means the die is hurting `current_player`. Returns 0.0 for an
+    /// empty cell and NaN for invalid input.
     #[wasm_bindgen]
-    pub fn get_master_move(
+    #[allow(clippy::too_many_arguments)]
+    pub fn eval_without_cell(
         &mut self,
         grid1: &[u8],
         grid2: &[u8],
         current_player: u8,
-        current_die: u8,
-        profile: &OpponentProfile,
-    ) -> i32 {
-        // Convert from JS arrays to GameState
-        let mut state = GameState {
-            grid1: Grid { data: [0; 9] },
-            grid2: Grid { data: [0; 9] },
-            current_player: if current_player == 0 { Player::Player1 } else { Player::Player2 },
-            current_die: if current_die == 0 { None } else { Some(current_die) },
-            phase: if current_die == 0 { GamePhase::Rolling } else { GamePhase::Placing },
-            turn_number: 1,
-        };
-        
-        // Copy grid data
-        let len1 = grid1.len().min(9);
-        for i in 0..len1 {
-            state.grid1.data[i] = grid1[i];
-        }
-        for i in len1..9 {
-            state.grid1.data[i] = 0;
-        }
-        let len2 = grid2.len().min(9);
-        for i in 0..len2 {
-            state.grid2.data[i] = grid2[i];
+        target_grid: u8,
+        col: u8,
+        row: u8,
+        depth: u32,
+    ) -> f64 {
+        if current_player > 1 || target_grid > 1 || col > 2 || row > 2
+            || grid1.len() > 9 || grid2.len() > 9
+            || !dice_in_range(grid1, grid2, 0, self.ctx.die_faces)
+        {
+            return f64::NAN;
         }
-        for i in len2..9 {
-            state.grid2.data[i] = 0;
+        let state = state_from_js(grid1, grid2, current_player, 0);
+        let mut without = state.clone();
+        let grid = if target_grid == 0 { &mut without.grid1 } else { &mut without.grid2 };
+        if grid.clear_cell(col as usize, row as usize) == 0 {
+            return 0.0;
         }
         
-        if state.phase != GamePhase::Placing || state.current_die.is_none() {
-            return -1;
+        analysis_value(without, depth, &mut self.ctx) - analysis_value(state, depth, &mut self.ctx)
+    }
+    
+    /// Count the columns of a grid that hold three dice of the same value
+    #[wasm_bindgen]
+    pub fn count_triples(&self, grid: &[u8]) -> u32 {
+        if grid.len() > 9 {
+            return 0;
         }
-        
-        let player = state.current_player;
-        let grid = match player {
-            Player::Player1 => &state.grid1,
-            Player::Player2 => &state.grid2,
-        };
-        
-        let legal_columns: Vec<usize> = (0..3)
-            .filter(|&col| !grid.is_column_full(col))
-            .collect();
-        
-        if legal_columns.is_empty() {
-            return -1;
+        let grid = Grid::from_slice(grid);
+        (0..3)
+            .filter(|&col| column_value_counts(&grid.column(col)).contains(&3))
+            .count() as u32
+    }
+    
+    /// Probability that the opponent removes the die at (`col`, `row`) of
+    /// `my_grid` on their next turn: the chance of rolling its value, or 0 when
+    /// the cell is empty or the opponent's matching column is full. This ignores
+    /// whether capturing would actually be the opponent's best move.
+    #[wasm_bindgen]
+    pub fn removal_probability(&self, my_grid: &[u8], opp_grid: &[u8], col: u8, row: u8) -> f64 {
+        if my_grid.len() > 9 || opp_grid.len() > 9 || col > 2 || row > 2 {
+            return 0.0;
         }
-        
-        if legal_columns.len() == 1 {
-            return legal_columns[0] as i32;
+        let (mine, theirs) = (Grid::from_slice(my_grid), Grid::from_slice(opp_grid));
+        let value = mine.get(col as usize, row as usize);
+        if value == 0 || value > self.ctx.die_faces || theirs.is_column_full(col as usize) {
+            return 0.0;
         }
-        
-        // Get adaptive config from profile
-        let adaptive_config = profile.get_adaptive_config();
-        
-        // Use expert-level opponent modeling with adversarial search
-        let opponent_config = DifficultyConfig {
-            depth: 3,
-            randomness: 0.0,
-            offense_weight: 0.5,
-            defense_weight: 0.5,
-            advanced_eval: true,
-            adversarial: true,
-            time_budget_ms: 0.0,
-        };
-        
-        // Setup context for adversarial search
-        self.ctx.use_adversarial = true;
-        self.ctx.start_time = js_sys::Date::now();
-        self.ctx.time_budget_ms = 100.0; // 100ms budget for master
-        self.ctx.aborted = false;
-        
-        // Order moves with adaptive bias from profile
-        let ordered = order_moves_with_profile(&state, &legal_columns, player, profile);
-        let mut best_move: i32 = -1;
-        let mut best_value = f64::NEG_INFINITY;
-        
-        for col in ordered {
-            if self.ctx.should_abort() {
-                break;
-            }
-            
-            if let Some(new_state) = apply_move(&state, col) {
-                let base_value = if new_state.phase == GamePhase::Ended {
-                    evaluate(&new_state, player, &adaptive_config)
-                } else {
-                    chance_node(&new_state, adaptive_config.depth.saturating_sub(1), player, &adaptive_config, &opponent_config, &mut self.ctx)
-                };
-                
-                // Apply column bias from learned opponent patterns
-                let column_bias = profile.get_column_attack_bonus(col);
-                let value = base_value + column_bias;
-                
-                if value > best_value {
-                    best_value = value;
-                    best_move = col as i32;
-                }
-            }
+        1.0 / self.ctx.die_faces as f64
+    }
+    
+    /// Solve a puzzle with a known sequence of upcoming rolls: the column to play
+    /// for each roll that maximizes the player's final score margin.
+    ///
+    /// Opponent assumption: this is the solitaire version, so the opponent makes
+    /// no moves between the player's turns; the player's placements still
+    /// capture matching dice from the opponent's (otherwise static) grid. The
+    /// plan stops early if the player's grid fills. Returns an empty vec for
+    /// invalid input.
+    #[wasm_bindgen]
+    pub fn solve_known_sequence(
+        &self,
+        grid1: &[u8],
+        grid2: &[u8],
+        current_player: u8,
+        rolls: &[u8],
+    ) -> Vec<u8> {
+        let faces = self.ctx.die_faces;
+        if current_player > 1 || grid1.len() > 9 || grid2.len() > 9
+            || !dice_in_range(grid1, grid2, 0, faces)
+            || rolls.iter().any(|&r| r == 0 || r > faces)
+        {
+            return Vec::new();
         }
-        
-        if best_move == -1 {
-            best_move = legal_columns[0] as i32;
+        let state = state_from_js(grid1, grid2, current_player, 0);
+        if state.grid1.is_full() || state.grid2.is_full() {
+            return Vec::new();
         }
-        
-        best_move
+        solve_solitaire_sequence(&state, state.current_player, rolls).1
     }
-}
-
-// ============================================================================
-// Master AI - Opponent Profile for Adaptive Learning
-// ============================================================================
-
-// Constants for Master AI adaptive learning
-/// Default frequency when no data available (1/3 for 3 columns)
-const UNIFORM_COLUMN_FREQUENCY: f64 = 0.333;
-
-/// Scaling factor for column preference bonus based on opponent usage patterns
-const COLUMN_PREFERENCE_SCALE: f64 = 3.0;
-
-/// Scaling factor for high dice placement bonus (targets opponent's high-value columns)
-const HIGH_DICE_BONUS_SCALE: f64 = 5.0;
-
-/// Multiplier for profile-based bonus in move ordering (balances learned patterns vs immediate value)
-const PROFILE_BONUS_MULTIPLIER: f64 = 2.0;
-
-/// Attack rate threshold for aggressive opponent detection
-const AGGRESSIVE_ATTACK_THRESHOLD: f64 = 0.4;
-
-/// Attack rate threshold for passive opponent detection  
-const PASSIVE_ATTACK_THRESHOLD: f64 = 0.2;
-
-/// Opponent behavior profile that learns patterns across games
-#[wasm_bindgen]
-pub struct OpponentProfile {
-    // Column usage frequency [col0, col1, col2]
-    column_usage: [u32; 3],
-    total_moves: u32,
     
-    // Attack stats: times opponent removed dice
-    attack_moves: u32,
-    
-    // Die placement patterns by column
-    // High dice (5-6) placements per column
-    high_dice_placements: [u32; 3],
-    // Low dice (1-2) placements per column
-    low_dice_placements: [u32; 3],
-    
-    // Total score lost to opponent attacks (for defense learning)
-    score_lost_to_attacks: u32,
-    
-    // Games completed for stability weighting
-    games_completed: u32,
-}
-
-#[wasm_bindgen]
-impl OpponentProfile {
-    /// Create a new empty opponent profile
-    #[wasm_bindgen(constructor)]
-    pub fn new() -> Self {
-        OpponentProfile {
-            column_usage: [0; 3],
-            total_moves: 0,
-            attack_moves: 0,
-            high_dice_placements: [0; 3],
-            low_dice_placements: [0; 3],
-            score_lost_to_attacks: 0,
-            games_completed: 0,
+    /// Whether placing `die` in `col` would remove any of the opponent's dice
+    #[wasm_bindgen]
+    pub fn move_captures(&self, grid_opp: &[u8], col: u8, die: u8) -> bool {
+        if grid_opp.len() > 9 || col > 2 || die == 0 {
+            return false;
         }
+        Grid::from_slice(grid_opp).column(col as usize).contains(&die)
     }
     
-    /// Record an opponent move for learning
+    /// Check a position from the frontend for desync bugs and return every problem
+    /// found as `PositionProblem` codes (empty when consistent). `turn_number`
+    /// starts at 1 and counts placements, so the board can hold at most
+    /// `turn_number - 1` dice (captures only ever remove dice). Never panics.
     #[wasm_bindgen]
-    pub fn record_move(&mut self, col: u8, die_value: u8, removed_count: u8, score_lost: u32) {
-        if col > 2 || die_value == 0 || die_value > 6 {
-            return;
-        }
-        
-        let col_idx = col as usize;
-        
-        self.column_usage[col_idx] = self.column_usage[col_idx].saturating_add(1);
-        self.total_moves = self.total_moves.saturating_add(1);
+    pub fn audit_position(
+        &self,
+        grid1: &[u8],
+        grid2: &[u8],
+        current_player: u8,
+        current_die: u8,
+        turn_number: u32,
+    ) -> Vec<u8> {
+        let faces = self.ctx.die_faces;
+        let mut problems = Vec::new();
+        let mut report = |problem: PositionProblem| {
+            if !problems.contains(&(problem as u8)) {
+                problems.push(problem as u8);
+            }
+        };
         
-        if removed_count > 0 {
-            self.attack_moves = self.attack_moves.saturating_add(1);
-            self.score_lost_to_attacks = self.score_lost_to_attacks.saturating_add(score_lost);
+        for cells in [grid1, grid2] {
+            if cells.len() != 9 {
+                report(PositionProblem::GridLength);
+            }
+            if cells.iter().any(|&v| v > faces) {
+                report(PositionProblem::InvalidCellValue);
+            }
+            if !Grid::from_slice(cells).respects_gravity() {
+                report(PositionProblem::GravityViolation);
+            }
+        }
+        if current_die > faces {
+            report(PositionProblem::DieOutOfRange);
+        }
+        if current_player > 1 {
+            report(PositionProblem::InvalidPlayer);
         }
         
-        if die_value >= 5 {
-            self.high_dice_placements[col_idx] = self.high_dice_placements[col_idx].saturating_add(1);
-        } else if die_value <= 2 {
-            self.low_dice_placements[col_idx] = self.low_dice_placements[col_idx].saturating_add(1);
+        let (g1, g2) = (Grid::from_slice(grid1), Grid::from_slice(grid2));
+        if current_die != 0 && (g1.is_full() || g2.is_full()) {
+            report(PositionProblem::DieAfterGameEnd);
         }
+        let dice_on_board = g1.data.iter().chain(g2.data.iter()).filter(|&&v| v != 0).count() as u64;
+        if turn_number == 0 || dice_on_board > turn_number as u64 - 1 {
+            report(PositionProblem::TurnNumberMismatch);
+        }
+        problems
     }
     
-    /// Mark end of game for stability tracking
+    /// Short human-readable label for a config, e.g.
+    /// "Hard — deep search, aggressive, occasionally random." The name follows the
+    /// depth of the built-in presets; the rest describes search depth, evaluation,
+    /// play style and randomness.
     #[wasm_bindgen]
-    pub fn end_game(&mut self) {
-        self.games_completed = self.games_completed.saturating_add(1);
+    pub fn describe_config(
+        &self,
+        depth: u32,
+        randomness: f64,
+        offense_weight: f64,
+        defense_weight: f64,
+        advanced_eval: bool,
+    ) -> String {
+        let name = match depth {
+            0 => "Greedy",
+            1 => "Beginner",
+            2 => "Easy",
+            3 => "Medium",
+            4 | 5 => "Hard",
+            _ => "Expert",
+        };
+        let mut traits = vec![match depth {
+            0 => "no lookahead",
+            1 | 2 => "shallow search",
+            3 => "moderate search",
+            _ => "deep search",
+        }];
+        if advanced_eval {
+            traits.push("positional play");
+        }
+        let lean = offense_weight - defense_weight;
+        traits.push(if lean > 0.15 {
+            "aggressive"
+        } else if lean < -0.15 {
+            "defensive"
+        } else {
+            "balanced"
+        });
+        if randomness > 0.3 {
+            traits.push("often random");
+        } else if randomness > 0.1 {
+            traits.push("sometimes random");
+        } else if randomness > 0.0 {
+            traits.push("occasionally random");
+        }
+        format!("{} — {}.", name, traits.join(", "))
     }
     
-    /// Reset all learned data
+    /// Generate a random grid with exactly `empties` empty cells for endgame
+    /// studies. Dice are stacked bottom-up in each column (respecting gravity)
+    /// with values drawn uniformly from the die, all from `seed`. Returns an
+    /// empty vec if `empties` is above 9.
     #[wasm_bindgen]
-    pub fn reset(&mut self) {
-        self.column_usage = [0; 3];
-        self.total_moves = 0;
-        self.attack_moves = 0;
-        self.high_dice_placements = [0; 3];
-        self.low_dice_placements = [0; 3];
-        self.score_lost_to_attacks = 0;
-        self.games_completed = 0;
+    pub fn fill_grid_random(&self, seed: u64, empties: u8) -> Vec<u8> {
+        if empties > 9 {
+            return Vec::new();
+        }
+        let mut rng = SeededRng::new(seed);
+        let mut grid = Grid::new();
+        for _ in 0..(9 - empties) {
+            let open: Vec<usize> = (0..3).filter(|&col| !grid.is_column_full(col)).collect();
+            let col = open[rng.below(open.len())];
+            let value = rng.below(self.ctx.die_faces as usize) as u8 + 1;
+            grid.place_die(col, value);
+        }
+        grid.data.to_vec()
     }
     
-    /// Get the number of games completed
+    /// Whether the advanced evaluation changes the chosen move: runs the root
+    /// search once with `advanced_eval` on and once off (same depth, balanced
+    /// weights) and compares the best columns. False for invalid input.
     #[wasm_bindgen]
-    pub fn get_games_completed(&self) -> u32 {
-        self.games_completed
+    pub fn move_differs_with_advanced(
+        &mut self,
+        grid1: &[u8],
+        grid2: &[u8],
+        current_player: u8,
+        current_die: u8,
+        depth: u32,
+    ) -> bool {
+        let state = match placing_state_from_js(grid1, grid2, current_player, current_die, self.ctx.die_faces) {
+            Ok(state) => state,
+            Err(_) => return false,
+        };
+        
+        let mut best = [None; 2];
+        for (slot, advanced_eval) in best.iter_mut().zip([true, false]) {
+            let config = DifficultyConfig { advanced_eval, ..analysis_config(depth) };
+            // Cached values from one evaluation must not leak into the other
+            self.ctx.begin_fixed_search(false);
+            let values = evaluate_root_moves(&state, state.current_player, &config, &config, &mut self.ctx);
+            *slot = values.iter()
+                .fold(None, |best: Option<(usize, f64)>, &(col, value)| match best {
+                    Some((_, best_value)) if best_value >= value => best,
+                    _ => Some((col, value)),
+                })
+                .map(|(col, _)| col);
+        }
+        self.ctx.clear();
+        best[0] != best[1]
     }
     
-    /// Get total moves recorded
+    /// Every legal column that forces a win within `max_depth` placements
+    /// (counting this one), whatever the dice and the opponent do. Exhaustive, so
+    /// meant for endgames; `max_depth` is capped at 8. Empty for invalid input or
+    /// when no move forces a win.
     #[wasm_bindgen]
-    pub fn get_total_moves(&self) -> u32 {
-        self.total_moves
+    pub fn all_winning_moves(
+        &self,
+        grid1: &[u8],
+        grid2: &[u8],
+        current_player: u8,
+        current_die: u8,
+        max_depth: u32,
+    ) -> Vec<u8> {
+        let state = match placing_state_from_js(grid1, grid2, current_player, current_die, self.ctx.die_faces) {
+            Ok(state) => state,
+            Err(_) => return Vec::new(),
+        };
+        let player = state.current_player;
+        let plies = max_depth.min(FORCED_WIN_MAX_PLIES);
+        if plies == 0 {
+            return Vec::new();
+        }
+        
+        let mut memo = HashMap::new();
+        get_legal_columns(&state).into_iter()
+            .filter(|&col| {
+                apply_move(&state, col)
+                    .is_some_and(|next| forces_win(&next, player, plies - 1, self.ctx.die_faces, &mut memo))
+            })
+            .map(|col| col as u8)
+            .collect()
     }
     
-    /// Get attack rate (0.0 to 1.0)
+    /// Normalized 18-cell board for deduplicating positions: the grid of the
+    /// side to move followed by the other grid, so a position and its
+    /// player-swapped twin (same grids, roles exchanged) give the same array.
+    /// Useful as an opening-book or clustering key. Returns an empty vec for
+    /// invalid input.
     #[wasm_bindgen]
-    pub fn get_attack_rate(&self) -> f64 {
-        if self.total_moves == 0 {
-            return 0.0;
+    pub fn canonical_board(&self, grid1: &[u8], grid2: &[u8], current_player: u8) -> Vec<u8> {
+        if current_player > 1 || grid1.len() > 9 || grid2.len() > 9 {
+            return Vec::new();
         }
-        self.attack_moves as f64 / self.total_moves as f64
+        let (mover, other) = if current_player == 0 { (grid1, grid2) } else { (grid2, grid1) };
+        let mut cells = Vec::with_capacity(18);
+        cells.extend_from_slice(&Grid::from_slice(mover).data);
+        cells.extend_from_slice(&Grid::from_slice(other).data);
+        cells
     }
     
-    /// Get column usage frequency for a column (0.0 to 1.0)
+    /// The positions one placement away: for each legal column in order, a
+    /// 21-byte record `[col, grid1 (9 cells), grid2 (9 cells), side_to_move,
+    /// phase]` with the side to move as 0/1 and the phase as 0 = rolling,
+    /// 2 = ended. Captures are applied exactly as in the search. Returns an
+    /// empty vec for invalid input or a position that isn't waiting on a
+    /// placement.
     #[wasm_bindgen]
-    pub fn get_column_frequency(&self, col: u8) -> f64 {
-        if col > 2 {
-            return 0.0;
-        }
-        if self.total_moves == 0 {
-            return UNIFORM_COLUMN_FREQUENCY;
+    pub fn successors(&self, grid1: &[u8], grid2: &[u8], current_player: u8, current_die: u8) -> Vec<u8> {
+        let state = match placing_state_from_js(grid1, grid2, current_player, current_die, self.ctx.die_faces) {
+            Ok(state) => state,
+            Err(_) => return Vec::new(),
+        };
+        let mut out = Vec::new();
+        for col in get_legal_columns(&state) {
+            if let Some(next) = apply_move(&state, col) {
+                out.push(col as u8);
+                encode_position(&next, &mut out);
+            }
         }
-        self.column_usage[col as usize] as f64 / self.total_moves as f64
+        out
     }
-}
-
-impl OpponentProfile {
-    /// Calculate adaptive difficulty config based on learned opponent patterns.
-    fn get_adaptive_config(&self) -> DifficultyConfig {
-        let mut config = DifficultyConfig {
-            depth: 5,
-            randomness: 0.0,
-            offense_weight: 0.5,
-            defense_weight: 0.5,
-            advanced_eval: true,
-            adversarial: true,
-            time_budget_ms: 100.0,
-        };
-        
-        if self.games_completed < 3 || self.total_moves < 10 {
-            return config;
+    
+    /// Whether to commit the next die to `col`: the `depth`-ply value of placing
+    /// it there minus the value of the best other column, averaged over the
+    /// roll, from `current_player`'s perspective. Positive means building in
+    /// `col` is worth it despite the risk of losing the stack; 0.0 when `col`
+    /// is the only open column. Returns NaN for invalid input, a finished
+    /// board, or a full `col`.
+    #[wasm_bindgen]
+    pub fn column_commitment_advice(
+        &mut self,
+        grid1: &[u8],
+        grid2: &[u8],
+        current_player: u8,
+        col: u8,
+        depth: u32,
+    ) -> f64 {
+        if current_player > 1 || col > 2 || grid1.len() > 9 || grid2.len() > 9
+            || !dice_in_range(grid1, grid2, 0, self.ctx.die_faces)
+        {
+            return f64::NAN;
         }
-        
-        let attack_rate = self.get_attack_rate();
-        
-        if attack_rate > AGGRESSIVE_ATTACK_THRESHOLD {
-            config.defense_weight = (0.6 + (attack_rate - AGGRESSIVE_ATTACK_THRESHOLD) * 0.5).clamp(0.0, 1.0);
-            config.offense_weight = 1.0 - config.defense_weight;
-        } else if attack_rate < PASSIVE_ATTACK_THRESHOLD {
-            config.offense_weight = 0.7;
-            config.defense_weight = 0.3;
+        let state = state_from_js(grid1, grid2, current_player, 0);
+        let player = state.current_player;
+        let my_grid = if player == Player::Player1 { &state.grid1 } else { &state.grid2 };
+        if state.grid1.is_full() || state.grid2.is_full() || my_grid.is_column_full(col as usize) {
+            return f64::NAN;
         }
         
-        config
+        let config = analysis_config(depth);
+        let faces = self.ctx.die_faces;
+        self.ctx.begin_fixed_search(false);
+        let mut total = 0.0;
+        for die_value in 1..=faces {
+            let values = evaluate_root_moves(&roll_die(&state, die_value), player, &config, &config, &mut self.ctx);
+            let committed = values.iter().find(|&&(c, _)| c == col as usize).map(|&(_, v)| v);
+            let elsewhere = values.iter()
+                .filter(|&&(c, _)| c != col as usize)
+                .map(|&(_, v)| v)
+                .fold(f64::NEG_INFINITY, f64::max);
+            if let Some(committed) = committed {
+                if elsewhere.is_finite() {
+                    total += (committed - elsewhere) / faces as f64;
+                }
+            }
+        }
+        total
     }
     
-    /// Get bonus for attacking a specific column based on opponent patterns.
-    fn get_column_attack_bonus(&self, col: usize) -> f64 {
-        if self.total_moves < 10 || col > 2 {
+    /// Expected number of opponent dice the player can remove this turn by
+    /// playing for captures: for each face, the most dice of that value in any
+    /// opponent column whose matching column of `my_grid` has room, averaged
+    /// over the roll. A cheap, profile-independent measure of how capture-rich
+    /// the position is. Returns 0.0 for invalid input.
+    #[wasm_bindgen]
+    pub fn expected_captures_this_turn(&self, my_grid: &[u8], opp_grid: &[u8]) -> f64 {
+        if my_grid.len() > 9 || opp_grid.len() > 9 {
             return 0.0;
         }
-        
-        let col_freq = self.column_usage[col] as f64 / self.total_moves as f64;
-        let preference_delta = col_freq - UNIFORM_COLUMN_FREQUENCY;
-        
-        let total_high_dice: u32 = self.high_dice_placements.iter().sum();
-        let high_dice_ratio = if total_high_dice > 0 {
-            self.high_dice_placements[col] as f64 / total_high_dice as f64
-        } else {
-            UNIFORM_COLUMN_FREQUENCY
-        };
-        
-        let high_dice_bonus = (high_dice_ratio - UNIFORM_COLUMN_FREQUENCY) * HIGH_DICE_BONUS_SCALE;
-        
-        preference_delta * COLUMN_PREFERENCE_SCALE + high_dice_bonus
+        let (mine, theirs) = (Grid::from_slice(my_grid), Grid::from_slice(opp_grid));
+        let faces = self.ctx.die_faces;
+        let total: usize = (1..=faces)
+            .map(|die_value| {
+                (0..3)
+                    .filter(|&col| !mine.is_column_full(col))
+                    .map(|col| theirs.column(col).iter().filter(|&&v| v == die_value).count())
+                    .max()
+                    .unwrap_or(0)
+            })
+            .sum();
+        total as f64 / faces as f64
     }
-}
-
-/// Order moves considering both quick evaluation and profile-based bias.
-fn order_moves_with_profile(
-    state: &GameState, 
-    columns: &[usize], 
-    player: Player,
-    profile: &OpponentProfile,
-) -> Vec<usize> {
-    if let Some(die_value) = state.current_die {
-        let mut scored: Vec<(usize, f64)> = columns.iter()
-            .map(|&col| {
-                let base_score = evaluate_move_quick(state, col, die_value, player);
-                let profile_bonus = profile.get_column_attack_bonus(col);
-                (col, base_score + profile_bonus * PROFILE_BONUS_MULTIPLIER)
+    
+    /// The opponent's legal columns for `opponent_die`, best for them first:
+    /// ranked by the points they would take off `my_grid` plus the points they
+    /// would add to `opp_grid`, ties in column order. Returns an empty vec when
+    /// they have no legal placement or for invalid input.
+    #[wasm_bindgen]
+    pub fn opponent_responses(&self, my_grid: &[u8], opp_grid: &[u8], opponent_die: u8) -> Vec<u8> {
+        if my_grid.len() > 9 || opp_grid.len() > 9 || opponent_die == 0 || opponent_die > self.ctx.die_faces {
+            return Vec::new();
+        }
+        let (mine, theirs) = (Grid::from_slice(my_grid), Grid::from_slice(opp_grid));
+        let mut ranked: Vec<(usize, i32)> = (0..3)
+            .filter(|&col| !theirs.is_column_full(col))
+            .map(|col| {
+                let swing = calculate_opponent_score_loss(&mine, col, opponent_die)
+                    + calculate_move_score_gain(&theirs, col, opponent_die);
+                (col, swing)
             })
             .collect();
-        scored.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
-        scored.into_iter().map(|(col, _)| col).collect()
-    } else {
-        columns.to_vec()
+        ranked.sort_by_key(|&(_, swing)| std::cmp::Reverse(swing));
+        ranked.into_iter().map(|(col, _)| col as u8).collect()
     }
-}
-
-// ============================================================================
-// Neural Network for Policy/Value Prediction
-// ============================================================================
-
-/// State encoding size:
-/// - 36 features for each player's grid (6 possible die values × 3 columns × 2 for count encoding)
-/// - Actually: 3 cols × 3 rows × 7 (one-hot for 0-6) × 2 players = 126
-/// - Simplified: 18 (grid1) + 18 (grid2) + 1 (current player) + 6 (current die one-hot) = 43
-/// Using compact encoding: counts per face per column for both players
-/// 6 faces × 3 columns × 2 players = 36 + 1 (player) + 6 (die one-hot) = 43
-
-const STATE_ENCODING_SIZE: usize = 43;
-const HIDDEN_SIZE: usize = 128;  // Doubled from 64 for more capacity
-const POLICY_OUTPUT_SIZE: usize = 3;  // 3 columns
-
-/// Encode game state into feature vector
-fn encode_state(state: &GameState) -> [f64; STATE_ENCODING_SIZE] {
-    let mut features = [0.0f64; STATE_ENCODING_SIZE];
-    let mut idx = 0;
     
-    // Encode grid1: counts per die value per column (6 × 3 = 18 features)
-    for col in 0..3 {
-        let mut counts = [0u8; 7]; // indices 1-6 used
-        for row in 0..3 {
-            let v = state.grid1.get(col, row);
-            if v > 0 && v <= 6 {
-                counts[v as usize] += 1;
-            }
+    /// Value of placing the current roll in `col`, searched `depth` plies with
+    /// every roll (this one included) drawn from `weights`, one non-negative
+    /// weight per face, normalized to sum to 1. The engine's fair die is left
+    /// untouched. From `current_player`'s perspective. Returns NaN for invalid
+    /// input, a finished board, or a full `col`.
+    #[wasm_bindgen]
+    pub fn move_value_under_distribution(
+        &mut self,
+        grid1: &[u8],
+        grid2: &[u8],
+        current_player: u8,
+        col: u8,
+        depth: u32,
+        weights: &[f64],
+    ) -> f64 {
+        let faces = self.ctx.die_faces as usize;
+        let total: f64 = weights.iter().sum();
+        if current_player > 1 || col > 2 || grid1.len() > 9 || grid2.len() > 9
+            || !dice_in_range(grid1, grid2, 0, self.ctx.die_faces)
+            || weights.len() != faces
+            || weights.iter().any(|&w| !w.is_finite() || w < 0.0)
+            || total <= 0.0
+        {
+            return f64::NAN;
         }
-        for die in 1..=6 {
-            features[idx] = counts[die] as f64 / 3.0; // Normalize by max possible
-            idx += 1;
+        let state = state_from_js(grid1, grid2, current_player, 0);
+        let player = state.current_player;
+        let my_grid = if player == Player::Player1 { &state.grid1 } else { &state.grid2 };
+        if state.grid1.is_full() || state.grid2.is_full() || my_grid.is_column_full(col as usize) {
+            return f64::NAN;
         }
-    }
-    
-    // Encode grid2: same format (18 features)
-    for col in 0..3 {
-        let mut counts = [0u8; 7];
-        for row in 0..3 {
-            let v = state.grid2.get(col, row);
-            if v > 0 && v <= 6 {
-                counts[v as usize] += 1;
-            }
+        
+        let mut probabilities = [0.0; MAX_DIE_FACES as usize];
+        for (p, &w) in probabilities.iter_mut().zip(weights) {
+            *p = w / total;
         }
-        for die in 1..=6 {
-            features[idx] = counts[die] as f64 / 3.0;
-            idx += 1;
+        let config = analysis_config(depth);
+        // Cached values were backed up under the fair die, and the ones written
+        // here must not outlive this call
+        self.ctx.clear();
+        self.ctx.roll_weights = Some(probabilities);
+        self.ctx.begin_fixed_search(false);
+        let mut value = 0.0;
+        for (die_value, &p) in (1..=self.ctx.die_faces).zip(probabilities.iter()) {
+            if p == 0.0 {
+                continue;
+            }
+            if let Some(next) = apply_move(&roll_die(&state, die_value), col as usize) {
+                let child = if next.phase == GamePhase::Ended {
+                    evaluate_terminal(&next, player, &config, config.depth - 1, &self.ctx)
+                } else {
+                    chance_node(&next, config.depth - 1, player, &config, &config, &mut self.ctx)
+                };
+                value += child * p;
+            }
         }
+        self.ctx.roll_weights = None;
+        self.ctx.clear();
+        value
     }
     
-    // Current player (1 feature)
-    features[idx] = if state.current_player == Player::Player1 { 0.0 } else { 1.0 };
-    idx += 1;
-    
-    // Current die one-hot (6 features)
-    if let Some(die) = state.current_die {
-        if die >= 1 && die <= 6 {
-            features[idx + (die as usize - 1)] = 1.0;
+    /// How much precise play matters in a position: the `depth`-ply value of
+    /// the best move minus the average value of all legal moves, from the
+    /// mover's perspective. Near zero means any move is about as good; 0.0
+    /// with a single legal move. Returns NaN for invalid input or a position
+    /// that isn't waiting on a placement.
+    #[wasm_bindgen]
+    pub fn skill_sensitivity(
+        &mut self,
+        grid1: &[u8],
+        grid2: &[u8],
+        current_player: u8,
+        current_die: u8,
+        depth: u32,
+    ) -> f64 {
+        let state = match placing_state_from_js(grid1, grid2, current_player, current_die, self.ctx.die_faces) {
+            Ok(state) => state,
+            Err(_) => return f64::NAN,
+        };
+        let config = analysis_config(depth);
+        self.ctx.begin_fixed_search(false);
+        let values = evaluate_root_moves(&state, state.current_player, &config, &config, &mut self.ctx);
+        if values.is_empty() {
+            return f64::NAN;
         }
+        let best = values.iter().map(|&(_, v)| v).fold(f64::NEG_INFINITY, f64::max);
+        let mean = values.iter().map(|&(_, v)| v).sum::<f64>() / values.len() as f64;
+        best - mean
     }
     
-    features
-}
-
-/// Simple MLP for policy and value prediction
-/// Architecture: input -> hidden (ReLU) -> policy head (softmax) + value head (tanh)
-#[wasm_bindgen]
-pub struct PolicyValueNetwork {
-    // Weights for input -> hidden layer
-    w1: Vec<f64>,  // HIDDEN_SIZE × STATE_ENCODING_SIZE
-    b1: Vec<f64>,  // HIDDEN_SIZE
-    
-    // Weights for hidden -> policy output
-    w_policy: Vec<f64>,  // POLICY_OUTPUT_SIZE × HIDDEN_SIZE
-    b_policy: Vec<f64>,  // POLICY_OUTPUT_SIZE
-    
-    // Weights for hidden -> value output
-    w_value: Vec<f64>,  // 1 × HIDDEN_SIZE
-    b_value: f64,
-    
-    // Whether weights have been loaded
-    initialized: bool,
-}
-
-#[wasm_bindgen]
-impl PolicyValueNetwork {
-    #[wasm_bindgen(constructor)]
-    pub fn new() -> Self {
-        // Initialize with small random weights (Xavier initialization)
-        let scale1 = (2.0 / (STATE_ENCODING_SIZE + HIDDEN_SIZE) as f64).sqrt();
-        let scale_policy = (2.0 / (HIDDEN_SIZE + POLICY_OUTPUT_SIZE) as f64).sqrt();
-        let scale_value = (2.0 / (HIDDEN_SIZE + 1) as f64).sqrt();
-        
-        let mut w1 = vec![0.0; HIDDEN_SIZE * STATE_ENCODING_SIZE];
-        let mut b1 = vec![0.0; HIDDEN_SIZE];
-        let mut w_policy = vec![0.0; POLICY_OUTPUT_SIZE * HIDDEN_SIZE];
-        let mut b_policy = vec![0.0; POLICY_OUTPUT_SIZE];
-        let mut w_value = vec![0.0; HIDDEN_SIZE];
-        
-        // Initialize with small random values
-        for i in 0..w1.len() {
-            w1[i] = (js_sys::Math::random() - 0.5) * 2.0 * scale1;
+    /// How many dice of each value a grid holds, as one count per face
+    /// (index 0 is the number of 1s). Returns an empty vec for invalid input.
+    #[wasm_bindgen]
+    pub fn grid_value_counts(&self, grid: &[u8]) -> Vec<u8> {
+        if grid.len() > 9 || grid.iter().any(|&v| v > self.ctx.die_faces) {
+            return Vec::new();
         }
-        for i in 0..w_policy.len() {
-            w_policy[i] = (js_sys::Math::random() - 0.5) * 2.0 * scale_policy;
+        Grid::from_slice(grid).value_counts()[1..=self.ctx.die_faces as usize].to_vec()
+    }
+    
+    /// Play a scripted sequence from a position with `to_move` about to roll:
+    /// `moves` holds (die, column) pairs applied in order, each a roll followed
+    /// by its placement. Returns the final position as a 20-byte record (both
+    /// grids, side to move, phase as in `successors`), or an empty vec if the
+    /// input is invalid or any step is illegal, including moving after the game
+    /// has ended.
+    #[wasm_bindgen]
+    pub fn simulate_sequence(&self, grid1: &[u8], grid2: &[u8], to_move: u8, moves: &[u8]) -> Vec<u8> {
+        if to_move > 1 || grid1.len() > 9 || grid2.len() > 9
+            || !dice_in_range(grid1, grid2, 0, self.ctx.die_faces)
+        {
+            return Vec::new();
         }
-        for i in 0..w_value.len() {
-            w_value[i] = (js_sys::Math::random() - 0.5) * 2.0 * scale_value;
+        let start = state_from_js(grid1, grid2, to_move, 0);
+        if start.grid1.is_full() || start.grid2.is_full() {
+            return Vec::new();
         }
-        
-        PolicyValueNetwork {
-            w1,
-            b1,
-            w_policy,
-            b_policy,
-            w_value,
-            b_value: 0.0,
-            initialized: false,
+        match replay_from(start, moves, self.ctx.die_faces) {
+            Some((_, end)) => {
+                let mut out = Vec::with_capacity(20);
+                encode_position(&end, &mut out);
+                out
+            }
+            None => Vec::new(),
         }
     }
     
-    /// Load weights from a flat array
-    /// Format: [w1..., b1..., w_policy..., b_policy..., w_value..., b_value]
+    /// Removal pressure each side exerts, as `[player1, player2]`: the points
+    /// that player is expected to knock off the other's grid with their next
+    /// roll, taking the biggest capture available for each face. High values on
+    /// both sides mean a tense position. Returns an empty vec for invalid input.
     #[wasm_bindgen]
-    pub fn load_weights(&mut self, weights: &[f64]) -> bool {
-        let expected_size = 
-            HIDDEN_SIZE * STATE_ENCODING_SIZE +  // w1
-            HIDDEN_SIZE +                         // b1
-            POLICY_OUTPUT_SIZE * HIDDEN_SIZE +    // w_policy
-            POLICY_OUTPUT_SIZE +                  // b_policy
-            HIDDEN_SIZE +                         // w_value
-            1;                                    // b_value
-        
-        if weights.len() != expected_size {
-            return false;
+    pub fn pressure(&self, grid1: &[u8], grid2: &[u8]) -> Vec<f64> {
+        if grid1.len() > 9 || grid2.len() > 9 || !dice_in_range(grid1, grid2, 0, self.ctx.die_faces) {
+            return Vec::new();
         }
+        let state = state_from_js(grid1, grid2, 0, 0);
+        let faces = self.ctx.die_faces;
+        vec![
+            capture_exposure(&state, Player::Player2, faces),
+            capture_exposure(&state, Player::Player1, faces),
+        ]
+    }
+    
+    /// Search a position and return a readable trace of the reasoning, for
+    /// attaching to bug reports about odd moves. One block per legal root move
+    /// with its backed-up value, the nodes and TT hits its subtree took, and
+    /// the opponent's expected reply to each roll (expectimax has no single
+    /// principal variation, since every line branches on the dice), then the
+    /// chosen column. The search is adversarial with the advanced evaluation
+    /// and the engine's settings. Only built with the `debug` feature.
+    #[cfg(feature = "debug")]
+    #[wasm_bindgen]
+    pub fn debug_search(
+        &mut self,
+        grid1: &[u8],
+        grid2: &[u8],
+        current_player: u8,
+        current_die: u8,
+        depth: u32,
+    ) -> String {
+        use std::fmt::Write;
         
-        let mut idx = 0;
+        let state = match placing_state_from_js(grid1, grid2, current_player, current_die, self.ctx.die_faces) {
+            Ok(state) => state,
+            Err(status) => return format!("no search: {:?}\n", status),
+        };
+        let player = state.current_player;
+        let config = DifficultyConfig {
+            depth: depth.max(1),
+            advanced_eval: true,
+            adversarial: true,
+            ..self.base_config
+        };
         
-        // Load w1
-        for i in 0..self.w1.len() {
-            self.w1[i] = weights[idx];
-            idx += 1;
+        self.ctx.begin_fixed_search(true);
+        let mut log = String::new();
+        let _ = writeln!(log, "position: grid1 {:?} grid2 {:?} to move {:?} die {} depth {}",
+            state.grid1.data, state.grid2.data, player, current_die, config.depth);
+        let mut best: Option<(usize, f64)> = None;
+        for col in get_legal_columns(&state) {
+            let Some(next) = apply_move_for(&state, col, &config) else { continue };
+            let (nodes_before, hits_before) = (self.ctx.nodes_explored, self.ctx.tt_hits);
+            let value = if next.phase == GamePhase::Ended {
+                evaluate_terminal(&next, player, &config, config.depth - 1, &self.ctx)
+            } else {
+                chance_node(&next, config.depth - 1, player, &config, &config, &mut self.ctx)
+            };
+            let _ = writeln!(log, "root col {}: value {:.3} nodes {} tt_hits {}",
+                col, value, self.ctx.nodes_explored - nodes_before, self.ctx.tt_hits - hits_before);
+            
+            if next.phase != GamePhase::Ended && config.depth >= 2 {
+                let mut replies = String::new();
+                for die_value in 1..=self.ctx.die_faces {
+                    let rolled = roll_die(&next, die_value);
+                    let reply = evaluate_root_moves(&rolled, player, &DifficultyConfig { depth: config.depth - 1, ..config }, &config, &mut self.ctx)
+                        .into_iter()
+                        .min_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal));
+                    if let Some((reply_col, _)) = reply {
+                        let _ = write!(replies, " {}->{}", die_value, reply_col);
+                    }
+                }
+                let _ = writeln!(log, "  replies (roll->col):{}", replies);
+            }
+            if best.is_none_or(|(_, v)| value > v) {
+                best = Some((col, value));
+            }
         }
-        
-        // Load b1
-        for i in 0..self.b1.len() {
-            self.b1[i] = weights[idx];
-            idx += 1;
+        let _ = match best {
+            Some((col, value)) => writeln!(log, "best: col {} value {:.3}", col, value),
+            None => writeln!(log, "best: none"),
+        };
+        log
+    }
+    
+    /// The battleground column: the one where both players together have
+    /// invested most, counting the dice in it plus its score on each side.
+    /// Ties go to the lowest column. Returns -1 for an empty board or invalid
+    /// input.
+    #[wasm_bindgen]
+    pub fn most_contested_column(&self, grid1: &[u8], grid2: &[u8]) -> i32 {
+        if grid1.len() > 9 || grid2.len() > 9 {
+            return -1;
         }
-        
-        // Load w_policy
-        for i in 0..self.w_policy.len() {
-            self.w_policy[i] = weights[idx];
-            idx += 1;
+        let (g1, g2) = (Grid::from_slice(grid1), Grid::from_slice(grid2));
+        let investment = |col: usize| -> i32 {
+            [g1.column(col), g2.column(col)].iter()
+                .map(|column| column.iter().filter(|&&v| v != 0).count() as i32 + calculate_column_score(column))
+                .sum()
+        };
+        let mut best: Option<(usize, i32)> = None;
+        for col in 0..3 {
+            let total = investment(col);
+            if total > 0 && best.is_none_or(|(_, b)| total > b) {
+                best = Some((col, total));
+            }
         }
-        
-        // Load b_policy
-        for i in 0..self.b_policy.len() {
-            self.b_policy[i] = weights[idx];
-            idx += 1;
+        best.map_or(-1, |(col, _)| col as i32)
+    }
+    
+    /// Set the thresholds `should_accept_draw` uses, in points: the largest
+    /// expected margin (either way) that counts as dead equal, and the smallest
+    /// spread of outcomes that makes playing on risky. Negative or non-finite
+    /// values restore the defaults (1.0 and 2.0).
+    #[wasm_bindgen]
+    pub fn set_draw_tolerance(&mut self, epsilon: f64, risk_tolerance: f64) {
+        self.draw_epsilon = if epsilon.is_finite() && epsilon >= 0.0 { epsilon } else { DEFAULT_DRAW_EPSILON };
+        self.draw_risk_tolerance = if risk_tolerance.is_finite() && risk_tolerance >= 0.0 {
+            risk_tolerance
+        } else {
+            DEFAULT_DRAW_RISK_TOLERANCE
+        };
+    }
+    
+    /// Whether the player to move should take a draw offer: their best move's
+    /// expected final margin after a `depth`-ply search (score-only evaluation)
+    /// is within the draw epsilon of zero, and the margin's standard deviation
+    /// over the opponent's next roll is at least the risk tolerance, so pressing
+    /// for a win is a gamble rather than a formality. Returns false for invalid
+    /// input or a position that isn't waiting on a placement.
+    #[wasm_bindgen]
+    pub fn should_accept_draw(
+        &mut self,
+        grid1: &[u8],
+        grid2: &[u8],
+        current_player: u8,
+        current_die: u8,
+        depth: u32,
+    ) -> bool {
+        let state = match placing_state_from_js(grid1, grid2, current_player, current_die, self.ctx.die_faces) {
+            Ok(state) => state,
+            Err(_) => return false,
+        };
+        let player = state.current_player;
+        let config = DifficultyConfig {
+            advanced_eval: false,
+            ..analysis_config(depth)
+        };
+        self.ctx.begin_fixed_search(false);
+        let best = evaluate_root_moves(&state, player, &config, &config, &mut self.ctx)
+            .into_iter()
+            .max_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal));
+        let Some((col, margin)) = best else { return false };
+        if margin.abs() > self.draw_epsilon {
+            return false;
         }
         
-        // Load w_value
-        for i in 0..self.w_value.len() {
-            self.w_value[i] = weights[idx];
-            idx += 1;
+        // Spread of the best line over the opponent's roll
+        let after = match apply_move(&state, col) {
+            Some(after) if after.phase != GamePhase::Ended => after,
+            _ => return false,
+        };
+        let faces = self.ctx.die_faces;
+        let outcomes: Vec<f64> = (1..=faces)
+            .map(|die_value| {
+                chance_node(&roll_die(&after, die_value), config.depth - 1, player, &config, &config, &mut self.ctx)
+            })
+            .collect();
+        let mean = outcomes.iter().sum::<f64>() / faces as f64;
+        let variance = outcomes.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / faces as f64;
+        variance.sqrt() >= self.draw_risk_tolerance
+    }
+    
+    /// Record a position (both grids and the side to move) in the engine's
+    /// history and return how many times it has now occurred, for
+    /// draw-by-repetition variants and for catching cycles in self-play. The
+    /// board only fills up between captures, but captures can restore an
+    /// earlier position: a die placed and then removed by a matching die that
+    /// is removed in turn leaves the board as it was. Returns 0 (recording
+    /// nothing) for invalid input.
+    #[wasm_bindgen]
+    pub fn record_position(&mut self, grid1: &[u8], grid2: &[u8], current_player: u8) -> u32 {
+        if current_player > 1 || grid1.len() > 9 || grid2.len() > 9
+            || !dice_in_range(grid1, grid2, 0, self.ctx.die_faces)
+        {
+            return 0;
         }
-        
-        // Load b_value
-        self.b_value = weights[idx];
-        
-        self.initialized = true;
-        true
+        let key = zobrist_board(&state_from_js(grid1, grid2, current_player, 0));
+        let count = self.position_history.entry(key).or_insert(0);
+        *count += 1;
+        self.last_position = Some(key);
+        *count
     }
     
-    /// Check if network has been initialized with weights
+    /// How many times the most recently recorded position has occurred (0 if
+    /// nothing has been recorded)
     #[wasm_bindgen]
-    pub fn is_initialized(&self) -> bool {
-        self.initialized
+    pub fn position_repeated(&self) -> u32 {
+        self.last_position
+            .and_then(|key| self.position_history.get(&key).copied())
+            .unwrap_or(0)
     }
     
-    /// Get the total number of weights in the network
+    /// Forget all recorded positions, e.g. at the start of a new game
     #[wasm_bindgen]
-    pub fn get_weight_count(&self) -> usize {
-        HIDDEN_SIZE * STATE_ENCODING_SIZE +
-        HIDDEN_SIZE +
-        POLICY_OUTPUT_SIZE * HIDDEN_SIZE +
-        POLICY_OUTPUT_SIZE +
-        HIDDEN_SIZE +
-        1
+    pub fn clear_position_history(&mut self) {
+        self.position_history.clear();
+        self.last_position = None;
     }
-}
-
-impl PolicyValueNetwork {
-    /// Forward pass: returns (policy, value)
-    /// Policy is a probability distribution over 3 columns
-    /// Value is in range [-1, 1]
-    fn forward(&self, state: &GameState) -> ([f64; 3], f64) {
-        let input = encode_state(state);
-        
-        // Hidden layer with ReLU
-        let mut hidden = [0.0f64; HIDDEN_SIZE];
-        for i in 0..HIDDEN_SIZE {
-            let mut sum = self.b1[i];
-            for j in 0..STATE_ENCODING_SIZE {
-                sum += self.w1[i * STATE_ENCODING_SIZE + j] * input[j];
-            }
-            hidden[i] = sum.max(0.0); // ReLU
-        }
-        
-        // Policy head with softmax
-        let mut policy_logits = [0.0f64; POLICY_OUTPUT_SIZE];
-        for i in 0..POLICY_OUTPUT_SIZE {
-            let mut sum = self.b_policy[i];
-            for j in 0..HIDDEN_SIZE {
-                sum += self.w_policy[i * HIDDEN_SIZE + j] * hidden[j];
-            }
-            policy_logits[i] = sum;
+    
+    /// The advanced evaluation's vulnerability term for one column: half the
+    /// total of `my_grid`'s dice in `col` while the opponent's matching column
+    /// has room, before the game-progress and defense-weight scaling. Returns
+    /// 0.0 for invalid input.
+    #[wasm_bindgen]
+    pub fn column_vulnerability(&self, my_grid: &[u8], opp_grid: &[u8], col: u8) -> f64 {
+        if my_grid.len() > 9 || opp_grid.len() > 9 || col > 2 {
+            return 0.0;
         }
-        
-        // Softmax
-        let max_logit = policy_logits.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
-        let mut exp_sum = 0.0;
-        let mut policy = [0.0f64; POLICY_OUTPUT_SIZE];
-        for i in 0..POLICY_OUTPUT_SIZE {
-            policy[i] = (policy_logits[i] - max_logit).exp();
-            exp_sum += policy[i];
+        let (mine, theirs) = (Grid::from_slice(my_grid), Grid::from_slice(opp_grid));
+        vulnerability_term(&mine.column(col as usize), &theirs.column(col as usize))
+    }
+    
+    /// How protected each of `my_grid`'s columns is, as `[col0, col1, col2]` in
+    /// 0.0..=1.0: the defensive counterpart to `column_equity`. A column is
+    /// fully safe (1.0) when the opposing column is full or there is nothing in
+    /// it to lose; otherwise safety drops with the share of the opposing column
+    /// still open and with the column's score as a share of the highest score a
+    /// column can hold. Returns an empty vec for invalid input.
+    #[wasm_bindgen]
+    pub fn column_safety(&self, my_grid: &[u8], opp_grid: &[u8]) -> Vec<f64> {
+        if my_grid.len() > 9 || opp_grid.len() > 9 || !dice_in_range(my_grid, opp_grid, 0, self.ctx.die_faces) {
+            return Vec::new();
         }
-        for i in 0..POLICY_OUTPUT_SIZE {
-            policy[i] /= exp_sum;
+        let (mine, theirs) = (Grid::from_slice(my_grid), Grid::from_slice(opp_grid));
+        let max_stake = calculate_column_score(&[self.ctx.die_faces; 3]) as f64;
+        (0..3)
+            .map(|col| {
+                if theirs.is_column_full(col) {
+                    return 1.0;
+                }
+                let stake = calculate_column_score(&mine.column(col)) as f64;
+                let open = theirs.column(col).iter().filter(|&&v| v == 0).count() as f64 / 3.0;
+                (1.0 - open * stake / max_stake).clamp(0.0, 1.0)
+            })
+            .collect()
+    }
+    
+    /// Static evaluation of the position as it stands, before any move, from
+    /// `current_player`'s perspective: the zero reference for showing move
+    /// values as deltas. Uses the advanced evaluation when `advanced_eval` is
+    /// set, otherwise the plain score margin. A board with a full grid is
+    /// scored as finished. Returns NaN for invalid input.
+    #[wasm_bindgen]
+    pub fn static_baseline(&self, grid1: &[u8], grid2: &[u8], current_player: u8, advanced_eval: bool) -> f64 {
+        if current_player > 1 || grid1.len() > 9 || grid2.len() > 9
+            || !dice_in_range(grid1, grid2, 0, self.ctx.die_faces)
+        {
+            return f64::NAN;
         }
-        
-        // Value head with tanh
-        let mut value_sum = self.b_value;
-        for j in 0..HIDDEN_SIZE {
-            value_sum += self.w_value[j] * hidden[j];
+        let mut state = state_from_js(grid1, grid2, current_player, 0);
+        if state.grid1.is_full() || state.grid2.is_full() {
+            state.phase = GamePhase::Ended;
         }
-        let value = value_sum.tanh();
-        
-        (policy, value)
+        let config = DifficultyConfig {
+            advanced_eval,
+            ..analysis_config(1)
+        };
+        evaluate(&state, state.current_player, &config)
     }
     
-    /// Get policy for legal moves only (renormalized)
-    fn get_policy_for_legal(&self, state: &GameState, legal_cols: &[usize]) -> Vec<(usize, f64)> {
-        let (policy, _) = self.forward(state);
-        
-        // Collect policy for legal moves
-        let mut legal_probs: Vec<(usize, f64)> = legal_cols.iter()
-            .map(|&col| (col, policy[col]))
-            .collect();
-        
-        // Renormalize
-        let sum: f64 = legal_probs.iter().map(|(_, p)| p).sum();
-        if sum > 0.0 {
-            for (_, p) in &mut legal_probs {
-                *p /= sum;
-            }
-        } else {
-            // Uniform if sum is 0
-            let uniform = 1.0 / legal_probs.len() as f64;
-            for (_, p) in &mut legal_probs {
-                *p = uniform;
+    /// Where the game gets decided along a line of best play: for each ply, the
+    /// gap between the best and second-best move values for the side to move
+    /// (0.0 with a single legal move). The line starts from this position and
+    /// roll and follows the best move each ply; since later rolls can't be
+    /// known, they are drawn from a copy of the engine's generator, so a seed
+    /// makes the line reproducible without advancing the engine's own rolls
+    /// (`next_die`). Unseeded, the copy is seeded from Math.random. Stops early
+    /// when the game ends; at most 18 plies. Returns an empty vec for invalid
+    /// input.
+    #[wasm_bindgen]
+    pub fn line_sharpness(
+        &mut self,
+        grid1: &[u8],
+        grid2: &[u8],
+        current_player: u8,
+        current_die: u8,
+        plies: u32,
+    ) -> Vec<f64> {
+        let mut state = match placing_state_from_js(grid1, grid2, current_player, current_die, self.ctx.die_faces) {
+            Ok(state) => state,
+            Err(_) => return Vec::new(),
+        };
+        let config = analysis_config(SHARPNESS_DEPTH);
+        let mut rng = self.ctx.rng
+            .unwrap_or_else(|| SeededRng::new((js_sys::Math::random() * u64::MAX as f64) as u64));
+        let faces = self.ctx.die_faces;
+        let mut spreads = Vec::new();
+        for _ in 0..plies.min(MAX_SHARPNESS_PLIES) {
+            self.ctx.begin_fixed_search(false);
+            let mut values = evaluate_root_moves(&state, state.current_player, &config, &config, &mut self.ctx);
+            values.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+            let Some(&(best_col, best_value)) = values.first() else { break };
+            spreads.push(values.get(1).map_or(0.0, |&(_, second)| best_value - second));
+            
+            match apply_move(&state, best_col) {
+                Some(next) if next.phase != GamePhase::Ended => {
+                    state = roll_die(&next, rng.below(faces as usize) as u8 + 1);
+                }
+                _ => break,
             }
         }
-        
-        legal_probs
+        spreads
     }
     
-    /// Get value estimate for a state
-    fn get_value(&self, state: &GameState) -> f64 {
-        let (_, value) = self.forward(state);
-        value
+    /// How sure the engine is of its move, in 0.0..1.0: the backed-up value gap
+    /// between the best and second-best root moves at `depth`, mapped through
+    /// `1 - exp(-gap / 6)`. Near 0.0 the choice barely matters; near 1.0 one
+    /// move clearly stands out. A forced move reports 1.0. Returns NaN for
+    /// invalid input.
+    #[wasm_bindgen]
+    pub fn move_confidence(
+        &mut self,
+        grid1: &[u8],
+        grid2: &[u8],
+        current_player: u8,
+        current_die: u8,
+        depth: u32,
+    ) -> f64 {
+        let state = match placing_state_from_js(grid1, grid2, current_player, current_die, self.ctx.die_faces) {
+            Ok(state) => state,
+            Err(_) => return f64::NAN,
+        };
+        let config = analysis_config(depth);
+        self.ctx.begin_fixed_search(false);
+        let mut values: Vec<f64> = evaluate_root_moves(&state, state.current_player, &config, &config, &mut self.ctx)
+            .into_iter()
+            .map(|(_, value)| value)
+            .collect();
+        values.sort_by(|a, b| b.partial_cmp(a).unwrap_or(std::cmp::Ordering::Equal));
+        match values[..] {
+            [best, second, ..] => 1.0 - (-(best - second) / CONFIDENCE_SCALE).exp(),
+            _ => 1.0,
+        }
     }
 }
 
 // ============================================================================
-// MCTS (Monte Carlo Tree Search) with PUCT
+// Self-Play
 // ============================================================================
 
-/// MCTS Configuration
-const MCTS_C_PUCT: f64 = 1.5;  // Exploration constant for PUCT
-const MCTS_DEFAULT_SIMULATIONS: u32 = 800;  // Default number of simulations
-
-/// MCTS Node representing a game state
-#[derive(Clone)]
-struct MCTSNode {
-    /// Number of times this node has been visited
-    visits: u32,
-    /// Total value accumulated from this node (from the perspective of the player who moved here)
-    total_value: f64,
-    /// Prior probability for this action (from policy network or uniform)
-    prior: f64,
-    /// Children keyed by action (column index 0-2)
-    children: HashMap<usize, MCTSNode>,
-    /// The player who is to move at this node
-    player_to_move: Player,
-    /// Whether this is a terminal state
-    is_terminal: bool,
-    /// Terminal value if is_terminal
-    terminal_value: f64,
+/// Fixed opponent used by `seed_sweep`: balanced depth-2 expectimax with no randomness
+fn sweep_baseline_config() -> DifficultyConfig {
+    DifficultyConfig {
+        depth: 2,
+        advanced_eval: true,
+        ..DifficultyConfig::default()
+    }
 }
 
-impl MCTSNode {
-    fn new(prior: f64, player_to_move: Player) -> Self {
-        MCTSNode {
-            visits: 0,
-            total_value: 0.0,
-            prior,
-            children: HashMap::new(),
-            player_to_move,
-            is_terminal: false,
-            terminal_value: 0.0,
-        }
-    }
-    
-    fn new_terminal(value: f64) -> Self {
-        MCTSNode {
-            visits: 1,
-            total_value: value,
-            prior: 1.0,
-            children: HashMap::new(),
-            player_to_move: Player::Player1,
-            is_terminal: true,
-            terminal_value: value,
-        }
-    }
-    
-    /// Get the mean value of this node
-    fn mean_value(&self) -> f64 {
-        if self.visits == 0 {
-            0.0
-        } else {
-            self.total_value / self.visits as f64
-        }
+/// Play a full game from the empty board, rolling dice from `ctx`'s generator.
+/// `configs[0]` plays as player 1 and `configs[1]` as player 2, each modeling the
+/// other with its actual config. Returns 0 or 1 for the winning config, 2 for a draw.
+fn play_self_play_game(first: Player, configs: [&DifficultyConfig; 2], ctx: &mut SearchContext) -> u32 {
+    let mut state = state_from_js(&[], &[], if first == Player::Player1 { 0 } else { 1 }, 0);
+    while state.phase != GamePhase::Ended {
+        let rolled = roll_die(&state, ctx.roll());
+        let (mover, other) = match rolled.current_player {
+            Player::Player1 => (configs[0], configs[1]),
+            Player::Player2 => (configs[1], configs[0]),
+        };
+        let (col, _) = choose_move(&rolled, mover, other, ctx);
+        state = match apply_move(&rolled, col) {
+            Some(next) => next,
+            None => break,
+        };
     }
     
-    /// PUCT score for child selection
-    fn puct_score(&self, child: &MCTSNode, parent_visits: u32) -> f64 {
-        let q = child.mean_value();
-        let u = MCTS_C_PUCT * child.prior * (parent_visits as f64).sqrt() / (1.0 + child.visits as f64);
-        q + u
+    let score1 = calculate_grid_score(&state.grid1);
+    let score2 = calculate_grid_score(&state.grid2);
+    match score1.cmp(&score2) {
+        std::cmp::Ordering::Greater => 0,
+        std::cmp::Ordering::Less => 1,
+        std::cmp::Ordering::Equal => 2,
     }
-    
-    /// Select the best child according to PUCT
-    fn select_child(&self) -> Option<usize> {
-        if self.children.is_empty() {
+}
+
+/// Value gap between the two best moves at which `move_confidence` reaches
+/// about 63%
+const CONFIDENCE_SCALE: f64 = 6.0;
+
+/// Search depth used to verify generated puzzles
+const PUZZLE_DEPTH: u32 = 2;
+
+/// Random positions tried before `generate_puzzle` gives up
+const PUZZLE_MAX_ATTEMPTS: u32 = 200;
+
+/// Accepted (min, max) value margin between the best and second-best move for
+/// easy, medium and hard puzzles
+const PUZZLE_MARGINS: [(f64, f64); 3] = [(12.0, f64::INFINITY), (6.0, 12.0), (3.0, 6.0)];
+
+/// Play random moves from the empty board for a random number of plies and roll
+/// a die for the side to move. Returns None if the game ended on the way.
+fn random_puzzle_position(ctx: &mut SearchContext) -> Option<GameState> {
+    let first = if ctx.random() < 0.5 { 0 } else { 1 };
+    let mut state = state_from_js(&[], &[], first, 0);
+    let plies = 4 + (ctx.random() * 9.0) as u32;
+    for _ in 0..plies {
+        let rolled = roll_die(&state, ctx.roll());
+        let legal = get_legal_columns(&rolled);
+        let col = legal[(ctx.random() * legal.len() as f64) as usize];
+        state = apply_move(&rolled, col)?;
+        if state.phase == GamePhase::Ended {
             return None;
         }
+    }
+    Some(roll_die(&state, ctx.roll()))
+}
+
+#[wasm_bindgen]
+impl AIEngine {
+    /// Play one game per seed between the given config and a fixed baseline
+    /// (depth-2 balanced expectimax) and return each winner: 0 for the config,
+    /// 1 for the baseline, 2 for a draw. The seed drives both the dice and any
+    /// random moves, and even seeds let the config move first, so identical
+    /// engines produce identical results. Time budgets are ignored to keep the
+    /// games deterministic. The engine's own seed is left untouched.
+    #[wasm_bindgen]
+    #[allow(clippy::too_many_arguments)]
+    pub fn seed_sweep(
+        &mut self,
+        depth: u32,
+        randomness: f64,
+        offense_weight: f64,
+        defense_weight: f64,
+        advanced_eval: bool,
+        adversarial: bool,
+        seeds: &[u64],
+    ) -> Vec<u32> {
+        let config = DifficultyConfig {
+            depth,
+            randomness,
+            offense_weight,
+            defense_weight,
+            advanced_eval,
+            adversarial,
+            ..self.base_config
+        };
+        let baseline = sweep_baseline_config();
+        let saved_rng = self.ctx.rng.take();
         
-        let mut best_action = None;
-        let mut best_score = f64::NEG_INFINITY;
-        
-        for (&action, child) in &self.children {
-            let score = self.puct_score(child, self.visits);
-            if score > best_score {
-                best_score = score;
-                best_action = Some(action);
-            }
-        }
+        let results = seeds.iter().map(|&seed| {
+            self.ctx.clear();
+            self.ctx.rng = Some(SeededRng::new(seed));
+            let first = if seed.is_multiple_of(2) { Player::Player1 } else { Player::Player2 };
+            play_self_play_game(first, [&config, &baseline], &mut self.ctx)
+        }).collect();
         
-        best_action
+        self.ctx.clear();
+        self.ctx.rng = saved_rng;
+        results
     }
     
-    /// Select the best action based on visit counts (for final move selection)
-    fn best_action_by_visits(&self) -> Option<usize> {
-        if self.children.is_empty() {
-            return None;
-        }
-        
-        let mut best_action = None;
-        let mut best_visits = 0;
+    /// Generate a puzzle position with one clearly best move.
+    ///
+    /// Random positions are set up from `seed` and verified with a depth-2
+    /// search; a position is accepted when its best move beats the runner-up by a
+    /// margin matching `difficulty` (0 easy: obvious, 1 medium, 2+ hard: subtle,
+    /// and the greedy move must be wrong). Returns grid1 (9 bytes), grid2 (9
+    /// bytes), the player to move and the die, or an empty array if no suitable
+    /// position was found.
+    #[wasm_bindgen]
+    pub fn generate_puzzle(&mut self, seed: u64, difficulty: u8) -> Vec<u8> {
+        let (min_margin, max_margin) = PUZZLE_MARGINS[(difficulty as usize).min(PUZZLE_MARGINS.len() - 1)];
+        let config = analysis_config(PUZZLE_DEPTH);
+        let saved_rng = self.ctx.rng.replace(SeededRng::new(seed));
+        let mut puzzle = Vec::new();
         
-        for (&action, child) in &self.children {
-            if child.visits > best_visits {
-                best_visits = child.visits;
-                best_action = Some(action);
+        for _ in 0..PUZZLE_MAX_ATTEMPTS {
+            let state = match random_puzzle_position(&mut self.ctx) {
+                Some(state) => state,
+                None => continue,
+            };
+            let player = state.current_player;
+            
+            self.ctx.begin_fixed_search(false);
+            let mut values = evaluate_root_moves(&state, player, &config, &config, &mut self.ctx);
+            if values.len() < 2 {
+                continue;
+            }
+            values.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+            let margin = values[0].1 - values[1].1;
+            if margin < min_margin || margin >= max_margin {
+                continue;
+            }
+            
+            let best_col = values[0].0;
+            if difficulty >= 2 {
+                let die_value = state.current_die.unwrap_or(0);
+                let greedy = DifficultyConfig::default();
+                let greedy_col = get_legal_columns(&state).into_iter()
+                    .max_by(|&a, &b| {
+                        evaluate_move_quick(&state, a, die_value, player, &greedy)
+                            .partial_cmp(&evaluate_move_quick(&state, b, die_value, player, &greedy))
+                            .unwrap_or(std::cmp::Ordering::Equal)
+                    });
+                if greedy_col == Some(best_col) {
+                    continue;
+                }
             }
+            
+            puzzle.extend_from_slice(&state.grid1.data);
+            puzzle.extend_from_slice(&state.grid2.data);
+            puzzle.push(if player == Player::Player1 { 0 } else { 1 });
+            puzzle.push(state.current_die.unwrap_or(0));
+            break;
         }
         
-        best_action
+        self.ctx.clear();
+        self.ctx.rng = saved_rng;
+        puzzle
     }
 }
 
-/// MCTS Search Context
-struct MCTSContext {
-    /// Root node
-    root: MCTSNode,
-    /// Start time for time budget
-    start_time: f64,
-    /// Time budget in ms
-    time_budget_ms: f64,
-    /// Number of simulations run
-    simulations: u32,
-    /// Player we're optimizing for
-    root_player: Player,
-}
-
-impl MCTSContext {
-    fn new(player: Player, time_budget_ms: f64) -> Self {
-        MCTSContext {
-            root: MCTSNode::new(1.0, player),
-            start_time: js_sys::Date::now(),
-            time_budget_ms,
-            simulations: 0,
-            root_player: player,
-        }
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::wasm_bindgen_test;
+    
+    /// Mid-game positions as (grid1, grid2, current_player, current_die)
+    const POSITIONS: [([u8; 9], [u8; 9], u8, u8); 4] = [
+        ([3, 0, 0, 5, 5, 0, 1, 0, 0], [2, 0, 0, 6, 0, 0, 4, 4, 0], 0, 4),
+        ([6, 2, 0, 1, 0, 0, 3, 3, 0], [5, 0, 0, 2, 2, 0, 6, 0, 0], 1, 2),
+        ([4, 4, 1, 2, 0, 0, 0, 0, 0], [1, 3, 0, 5, 0, 0, 6, 6, 0], 0, 6),
+        ([1, 0, 0, 0, 0, 0, 2, 0, 0], [3, 0, 0, 0, 0, 0, 0, 0, 0], 1, 3),
+    ];
+    
+    /// A context that needs no JS: no time budget and a seeded generator
+    fn test_ctx() -> SearchContext {
+        let mut ctx = SearchContext::new();
+        ctx.rng = Some(SeededRng::new(1));
+        ctx
+    }
+    
+    fn depth_config(depth: u32) -> DifficultyConfig {
+        DifficultyConfig { depth, ..DifficultyConfig::default() }
+    }
+    
+    /// What `begin_search` does for a new move search, minus the JS clock
+    fn restart(ctx: &mut SearchContext) {
+        ctx.aborted = false;
+        ctx.nodes_explored = 0;
+        ctx.opponent_nodes = 0;
+        ctx.tt_probes = 0;
+        ctx.tt_hits = 0;
+        ctx.counters = SearchCounters::default();
+    }
+    
+    #[test]
+    fn d8_chance_nodes_average_over_all_eight_faces() {
+        let state = state_from_js(&[1, 0, 0, 3, 0, 0, 0, 0, 0], &[7, 0, 0, 0, 0, 0, 8, 0, 0], 0, 0);
+        let config = depth_config(1);
+        let mut ctx = test_ctx();
+        ctx.die_faces = 8;
+        let value = chance_node(&state, 1, Player::Player1, &config, &config, &mut ctx);
+        
+        let expected = (1..=8u8)
+            .map(|die| {
+                let rolled = roll_die(&state, die);
+                (0..3)
+                    .filter_map(|col| apply_move(&rolled, col))
+                    .map(|next| evaluate(&next, Player::Player1, &config))
+                    .fold(f64::NEG_INFINITY, f64::max)
+            })
+            .sum::<f64>()
+            / 8.0;
+        assert!((value - expected).abs() < 1e-9, "{value} vs {expected}");
+    }
+    
+    #[test]
+    fn d8_search_places_an_eight_to_capture_eights() {
+        let state = state_from_js(&[0; 9], &[0, 0, 0, 8, 8, 0, 0, 0, 0], 0, 8);
+        let config = depth_config(2);
+        let mut ctx = test_ctx();
+        ctx.die_faces = 8;
+        let (col, _) = expectimax_internal(&state, Player::Player1, &config, &config, &mut ctx);
+        assert_eq!(col, Some(1));
+    }
+    
+    #[test]
+    fn profile_records_dice_above_six() {
+        let mut profile = OpponentProfile::new();
+        profile.record_move(0, 8, 0, 0);
+        profile.record_move(0, 7, 0, 0);
+        assert_eq!(profile.get_total_moves(), 2);
+        assert_eq!(profile.high_dice_placements[0], 2);
     }
     
-    fn should_stop(&self) -> bool {
-        if self.time_budget_ms <= 0.0 {
-            return self.simulations >= MCTS_DEFAULT_SIMULATIONS;
+    #[test]
+    fn maximin_avoids_the_greedy_move_that_invites_a_capture() {
+        // Doubling the 6 in column 0 scores most now, but the opponent can only
+        // play into column 0 and a rolled 6 would wipe both
+        let grid1 = [6, 0, 0, 0, 0, 0, 0, 0, 0];
+        let grid2 = [0, 0, 0, 1, 2, 3, 1, 2, 4];
+        let state = state_from_js(&grid1, &grid2, 0, 6);
+        let greedy = order_moves(&state, &get_legal_columns(&state), Player::Player1)[0];
+        assert_eq!(greedy, 0);
+        
+        let worst_reply = |col: usize| {
+            let next = apply_move(&state, col).unwrap();
+            (1..=6).filter_map(|die| best_opponent_reply(&next, die, Player::Player1)).map(|(_, m)| m).fold(f64::INFINITY, f64::min)
+        };
+        let engine = AIEngine::new();
+        let maximin = engine.maximin_move(&grid1, &grid2, 0, 6);
+        assert_ne!(maximin, 0);
+        assert!(worst_reply(maximin as usize) > worst_reply(0));
+    }
+    
+    fn profile_after_games(games: u32, moves_per_game: u32, column_of: impl Fn(u32) -> u8) -> OpponentProfile {
+        let mut profile = OpponentProfile::new();
+        for _ in 0..games {
+            for i in 0..moves_per_game {
+                profile.record_move(column_of(i), 3, 0, 0);
+            }
+            profile.end_game();
         }
-        let elapsed = js_sys::Date::now() - self.start_time;
-        elapsed >= self.time_budget_ms
+        profile
     }
-}
-
-/// Get legal columns for a state
-fn get_legal_columns(state: &GameState) -> Vec<usize> {
-    let grid = match state.current_player {
-        Player::Player1 => &state.grid1,
-        Player::Player2 => &state.grid2,
-    };
     
-    (0..3).filter(|&col| !grid.is_column_full(col)).collect()
-}
-
-/// Get uniform prior for legal actions
-fn get_uniform_prior(num_legal: usize) -> f64 {
-    if num_legal == 0 {
-        0.0
-    } else {
-        1.0 / num_legal as f64
+    #[test]
+    fn learning_progress_counts_uniform_play_as_learned() {
+        let uniform = profile_after_games(10, 30, |i| (i % 3) as u8);
+        let progress = uniform.learning_progress();
+        assert!((0.5..0.9).contains(&progress), "{progress}");
+        
+        let lopsided = profile_after_games(10, 30, |_| 0);
+        assert!(lopsided.learning_progress() > 0.95);
+        assert!(lopsided.learning_progress() > progress);
+        assert_eq!(OpponentProfile::new().learning_progress(), 0.0);
     }
-}
-
-/// Evaluate a state from a player's perspective (normalized to [-1, 1])
-fn evaluate_normalized(state: &GameState, player: Player) -> f64 {
-    let config = DifficultyConfig {
-        depth: 0,
-        randomness: 0.0,
-        offense_weight: 0.5,
-        defense_weight: 0.5,
-        advanced_eval: true,
-        adversarial: true,
-        time_budget_ms: 0.0,
-    };
     
-    let raw_eval = evaluate_advanced(state, player, &config);
+    #[wasm_bindgen_test]
+    fn large_handicap_models_a_greedy_opponent() {
+        let mut engine = AIEngine::new();
+        for (grid1, grid2, player, die) in POSITIONS {
+            engine.clear_cache();
+            let handicapped = engine.get_best_move_handicap(&grid1, &grid2, player, die, 3, 10);
+            let handicapped_nodes = engine.last_search_stats().get_nodes();
+            engine.clear_cache();
+            let greedy = engine.get_best_move(&grid1, &grid2, player, die, 3, 0.0, 0.5, 0.5, true, 0, 0.0, 0.5, 0.5, true);
+            assert_eq!(handicapped, greedy);
+            assert_eq!(handicapped_nodes, engine.last_search_stats().get_nodes());
+        }
+    }
     
-    // Handle terminal states
-    if raw_eval >= 10000.0 {
-        return 1.0;
-    } else if raw_eval <= -10000.0 {
-        return -1.0;
+    #[wasm_bindgen_test]
+    fn best_moves_both_matches_single_searches() {
+        for (grid1, grid2, _, die) in POSITIONS {
+            let both = AIEngine::new().best_moves_both(&grid1, &grid2, die, 3);
+            let single: Vec<i32> = (0..2u8)
+                .map(|player| AIEngine::new().get_best_move(&grid1, &grid2, player, die, 3, 0.0, 0.5, 0.5, true, 3, 0.0, 0.5, 0.5, true))
+                .collect();
+            assert_eq!(both, single);
+        }
     }
     
-    // Normalize to roughly [-1, 1] range
-    // Typical score differences are in range [-200, 200]
-    (raw_eval / 200.0).clamp(-1.0, 1.0)
-}
-
-/// Expand a node by adding children for all legal actions
-fn mcts_expand(node: &mut MCTSNode, state: &GameState) {
-    if state.phase == GamePhase::Ended {
-        return;
+    fn greedy_outcome(grid1: &[u8], grid2: &[u8], player: u8, die: u8) -> MoveOutcome {
+        AIEngine::new().get_best_move_outcome(grid1, grid2, player, die, 0, 0.0, 0.5, 0.5, false, false, 0.0, 0, 0.0, 0.5, 0.5, false, false, 0.0)
     }
     
-    let legal_columns = get_legal_columns(state);
-    if legal_columns.is_empty() {
-        return;
+    #[test]
+    fn move_outcome_reports_each_status() {
+        let open = [1, 0, 0, 2, 0, 0, 3, 0, 0];
+        let ok = greedy_outcome(&open, &open, 0, 4);
+        assert_eq!((ok.get_status(), ok.is_ok()), (MoveStatus::Ok, true));
+        assert!((0..3).contains(&ok.get_column()));
+        
+        let full = [1, 2, 3, 4, 5, 6, 1, 2, 3];
+        for (grid1, grid2, player, die, status) in [
+            (&full[..], &open[..], 1, 4, MoveStatus::GameEnded),
+            // The mover's own grid being full is also a finished game
+            (&full[..], &open[..], 0, 4, MoveStatus::GameEnded),
+            (&open[..], &open[..], 2, 4, MoveStatus::InvalidInput),
+            (&open[..], &open[..], 0, 7, MoveStatus::InvalidInput),
+            (&[0; 10][..], &open[..], 0, 4, MoveStatus::InvalidInput),
+            (&open[..], &open[..], 0, 0, MoveStatus::NotPlacing),
+        ] {
+            let outcome = greedy_outcome(grid1, grid2, player, die);
+            assert_eq!(outcome.get_status(), status);
+            assert_eq!(outcome.get_column(), -1);
+            assert!(!outcome.is_ok());
+        }
     }
     
-    let prior = get_uniform_prior(legal_columns.len());
+    #[test]
+    fn legacy_best_move_reads_inputs_leniently() {
+        let greedy = |grid1: &[u8], grid2: &[u8], player: u8| {
+            AIEngine::new().get_best_move(grid1, grid2, player, 5, 0, 0.0, 0.5, 0.5, false, 0, 0.0, 0.5, 0.5, false)
+        };
+        let (grid1, grid2) = ([5, 0, 0, 2, 0, 0, 3, 0, 0], [1, 0, 0, 5, 5, 0, 4, 0, 0]);
+        // Player2 adds a third 5 to their pair, whatever nonzero code names them
+        assert_eq!(greedy(&grid1, &grid2, 1), 1);
+        assert_eq!(greedy(&grid1, &grid2, 2), 1);
+        // Cells past the ninth are ignored
+        let long: Vec<u8> = grid1.iter().copied().chain([6, 6]).collect();
+        assert_eq!(greedy(&long, &grid2, 1), 1);
+        // The outcome form stays strict
+        assert_eq!(greedy_outcome(&grid1, &grid2, 2, 5).get_status(), MoveStatus::InvalidInput);
+        assert_eq!(greedy_outcome(&long, &grid2, 1, 5).get_status(), MoveStatus::InvalidInput);
+    }
     
-    for col in legal_columns {
-        if !node.children.contains_key(&col) {
-            // Determine the player who will move after this action
-            let next_player = match state.current_player {
-                Player::Player1 => Player::Player2,
-                Player::Player2 => Player::Player1,
-            };
-            node.children.insert(col, MCTSNode::new(prior, next_player));
+    #[wasm_bindgen_test]
+    fn best_move_has_the_highest_column_equity() {
+        for (grid1, grid2, player, die) in POSITIONS {
+            let mut engine = AIEngine::new();
+            let equity = engine.column_equity(&grid1, &grid2, player, die, 3);
+            let best = engine.get_best_move(&grid1, &grid2, player, die, 3, 0.0, 0.5, 0.5, true, 3, 0.0, 0.5, 0.5, true);
+            let top = equity.iter().copied().filter(|e| !e.is_nan()).fold(f64::NEG_INFINITY, f64::max);
+            assert_eq!(equity[best as usize], top);
+            assert!(equity.iter().filter(|e| !e.is_nan()).sum::<f64>().abs() < 1e-9);
         }
     }
-}
-
-/// Simulate a game from a state to get a value
-fn mcts_simulate(state: &GameState, player: Player) -> f64 {
-    // Use evaluation function as a quick rollout replacement
-    evaluate_normalized(state, player)
-}
-
-/// Run one MCTS iteration (selection, expansion, simulation, backpropagation)
-fn mcts_iterate(
-    node: &mut MCTSNode,
-    state: &GameState,
-    root_player: Player,
-) -> f64 {
-    node.visits += 1;
     
-    // Terminal state
-    if state.phase == GamePhase::Ended {
-        let value = evaluate_normalized(state, root_player);
-        node.is_terminal = true;
-        node.terminal_value = value;
-        node.total_value += value;
-        return value;
+    /// Move grid columns one place to the right, wrapping around, `r` times
+    fn rotate_columns(grid: &[u8; 9], r: usize) -> [u8; 9] {
+        std::array::from_fn(|i| grid[((i / 3 + 3 - r) % 3) * 3 + i % 3])
     }
     
-    // Handle chance nodes (rolling phase) - sample a die
-    if state.phase == GamePhase::Rolling {
-        // Sample a random die value
-        let die_value = (js_sys::Math::random() * 6.0) as u8 + 1;
-        let rolled_state = roll_die(state, die_value);
-        let value = mcts_iterate(node, &rolled_state, root_player);
-        node.total_value += value;
-        return value;
+    #[test]
+    fn fair_root_choice_does_not_depend_on_move_order() {
+        // The 5 matches and captures nothing, so every root move orders the
+        // same and rotating the columns changes which one is searched first
+        let grid1 = [1, 0, 0, 2, 2, 0, 3, 0, 0];
+        let grid2 = [4, 0, 0, 6, 0, 0, 4, 6, 0];
+        let config = DifficultyConfig { depth: 6, advanced_eval: true, ..DifficultyConfig::default() };
+        let picks = |fair: bool| -> Vec<usize> {
+            (0..3)
+                .map(|r| {
+                    let state = state_from_js(&rotate_columns(&grid1, r), &rotate_columns(&grid2, r), 0, 5);
+                    let mut ctx = test_ctx();
+                    ctx.max_nodes = 2000;
+                    ctx.fair_root_on_cap = fair;
+                    let (col, _) = expectimax_internal(&state, Player::Player1, &config, &config, &mut ctx);
+                    (col.unwrap() + 3 - r) % 3
+                })
+                .collect()
+        };
+        // Capped without the fair re-search, whichever move came first wins
+        assert_eq!(picks(false), vec![1, 0, 2]);
+        assert_eq!(picks(true), vec![2, 2, 2]);
     }
     
-    // Expand if not expanded
-    if node.children.is_empty() {
-        mcts_expand(node, state);
-        
-        // If still no children, this is a terminal-like state
-        if node.children.is_empty() {
-            let value = evaluate_normalized(state, root_player);
-            node.total_value += value;
-            return value;
-        }
-        
-        // Select one child for rollout
-        if let Some(action) = node.select_child() {
-            if let Some(new_state) = apply_move(state, action) {
-                let value = mcts_simulate(&new_state, root_player);
-                if let Some(child) = node.children.get_mut(&action) {
-                    child.visits += 1;
-                    child.total_value += value;
-                }
-                node.total_value += value;
-                return value;
+    #[test]
+    fn transcript_learning_matches_live_recording() {
+        let dice = [3, 5, 1, 6, 6, 2, 4, 5, 2, 1, 3, 6, 4];
+        let mut state = state_from_js(&[], &[], 0, 0);
+        let mut transcript = vec![0u8];
+        let mut live = OpponentProfile::new();
+        while state.phase != GamePhase::Ended {
+            let die = dice[(transcript.len() / 2) % dice.len()];
+            let rolled = roll_die(&state, die);
+            let mover = rolled.current_player;
+            let col = order_moves(&rolled, &get_legal_columns(&rolled), mover)[0];
+            let next = apply_move(&rolled, col).unwrap();
+            if mover == Player::Player2 {
+                // What the frontend sees: dice gone from Player1's column and
+                // the score that went with them
+                let removed = rolled.grid1.column(col).iter().filter(|&&v| v == die).count() as u8;
+                let lost = calculate_grid_score(&rolled.grid1) - calculate_grid_score(&next.grid1);
+                live.record_move(col as u8, die, removed, lost as u32);
             }
+            transcript.extend([die, col as u8]);
+            state = next;
         }
+        live.end_game();
         
-        let value = evaluate_normalized(state, root_player);
-        node.total_value += value;
-        return value;
+        let mut learned = OpponentProfile::new();
+        assert!(learned.learn_from_transcript(&transcript, 1));
+        assert!(live.get_total_moves() > 0);
+        assert_eq!(learned.export_full(), live.export_full());
     }
     
-    // Selection - pick best child via PUCT
-    if let Some(action) = node.select_child() {
-        if let Some(new_state) = apply_move(state, action) {
-            // Recursively iterate
-            if let Some(child) = node.children.get_mut(&action) {
-                let value = mcts_iterate(child, &new_state, root_player);
-                node.total_value += value;
-                return value;
+    #[wasm_bindgen_test]
+    fn min_depth_forces_a_search_at_depth_zero() {
+        let (grid1, grid2, player, die) = POSITIONS[0];
+        let mut engine = AIEngine::new();
+        engine.get_best_move(&grid1, &grid2, player, die, 0, 0.0, 0.5, 0.5, true, 0, 0.0, 0.5, 0.5, true);
+        let stats = engine.last_search_stats();
+        assert!(stats.is_greedy());
+        assert_eq!(stats.get_nodes(), 0);
+        
+        engine.set_min_depth(2);
+        engine.get_best_move(&grid1, &grid2, player, die, 0, 0.0, 0.5, 0.5, true, 0, 0.0, 0.5, 0.5, true);
+        let stats = engine.last_search_stats();
+        assert!(!stats.is_greedy());
+        assert_eq!(stats.get_depth_reached(), 2);
+        assert!(stats.get_nodes() > 0);
+    }
+    
+    /// A profile that has seen each (column, die, times) placement `times` times
+    fn profile_with_moves(moves: &[(u8, u8, u32)]) -> OpponentProfile {
+        let mut profile = OpponentProfile::new();
+        for &(col, die, times) in moves {
+            for _ in 0..times {
+                profile.record_move(col, die, 0, 0);
             }
         }
+        profile
     }
     
-    // Fallback
-    let value = evaluate_normalized(state, root_player);
-    node.total_value += value;
-    value
-}
-
-/// Run MCTS search and return the best action
-fn mcts_search(state: &GameState, time_budget_ms: f64) -> Option<usize> {
-    if state.phase != GamePhase::Placing || state.current_die.is_none() {
-        return None;
+    #[test]
+    fn key_column_bonus_exceeds_the_sum_of_its_signals() {
+        // Favored column 0, with the high dice spread evenly
+        let favored = profile_with_moves(&[(0, 6, 2), (0, 3, 4), (1, 6, 2), (1, 3, 1), (2, 6, 2), (2, 3, 1)]);
+        // Even column usage, with every high die in column 0
+        let loaded = profile_with_moves(&[(0, 6, 4), (1, 3, 4), (2, 3, 4)]);
+        // Both: favored column 0 holding every high die
+        let mut key = profile_with_moves(&[(0, 6, 6), (1, 3, 3), (2, 3, 3)]);
+        
+        let sum = favored.get_column_attack_bonus(0) + loaded.get_column_attack_bonus(0);
+        assert!(favored.get_column_attack_bonus(0) > 0.0 && loaded.get_column_attack_bonus(0) > 0.0);
+        assert!(key.get_column_attack_bonus(0) > sum + 1.0, "{} vs {}", key.get_column_attack_bonus(0), sum);
+        
+        key.set_key_column_scale(0.0);
+        assert!((key.get_column_attack_bonus(0) - sum).abs() < 0.01);
     }
     
-    let player = state.current_player;
-    let legal_columns = get_legal_columns(state);
+    #[wasm_bindgen_test]
+    fn robust_choice_hedges_across_opponent_models() {
+        let grid1 = [4, 1, 0, 6, 0, 0, 6, 5, 0];
+        let grid2 = [5, 2, 0, 4, 2, 0, 0, 0, 0];
+        let mut engine = AIEngine::new();
+        assert!(engine.opponent_models_disagree(&grid1, &grid2, 0, 3, 2));
+        let robust = engine.opponent_robustness(&grid1, &grid2, 0, 3, 2) as usize;
+        
+        let state = state_from_js(&grid1, &grid2, 0, 3);
+        let per_model: Vec<Vec<(usize, f64)>> = robustness_opponent_models().iter()
+            .map(|model| {
+                let mut ctx = test_ctx();
+                ctx.use_adversarial = model.adversarial;
+                evaluate_root_moves(&state, Player::Player1, &analysis_config(2), model, &mut ctx)
+            })
+            .collect();
+        let worst_case = |col: usize| per_model.iter().map(|values| values[col].1).fold(f64::INFINITY, f64::min);
+        let best_against = |values: &Vec<(usize, f64)>| values.iter().copied().fold((0, f64::NEG_INFINITY), |b, v| if v.1 > b.1 { v } else { b }).0;
+        
+        // Against the greedy model another column looks best, but it does worse
+        // than the robust column if the opponent turns out stronger
+        let greedy_best = best_against(&per_model[0]);
+        assert_ne!(greedy_best, robust);
+        assert!(worst_case(robust) > worst_case(greedy_best));
+        assert_eq!(robust, best_against(&per_model[2]));
+    }
     
-    if legal_columns.is_empty() {
-        return None;
+    /// Random positions with up to two dice per column on each side, and a die
+    /// to place, from a fixed seed
+    fn random_positions(seed: u64, count: usize) -> Vec<GameState> {
+        let mut rng = SeededRng::new(seed);
+        let mut roll = move || (rng.next_f64() * 6.0) as u8 + 1;
+        (0..count)
+            .map(|_| {
+                let mut grids = [[0u8; 9]; 2];
+                for grid in grids.iter_mut() {
+                    for col in 0..3 {
+                        let filled = roll() as usize % 3;
+                        for row in 0..filled {
+                            grid[col * 3 + row] = roll();
+                        }
+                    }
+                }
+                let player = roll() % 2;
+                state_from_js(&grids[0], &grids[1], player, roll())
+            })
+            .collect()
     }
     
-    if legal_columns.len() == 1 {
-        return Some(legal_columns[0]);
+    #[test]
+    fn table_ordering_matches_the_quick_evaluation() {
+        // Equal scores for every move mean the same order, and so the same
+        // search and the same chosen move
+        let config = DifficultyConfig::default();
+        for state in random_positions(5, 2000) {
+            let die = state.current_die.unwrap();
+            for col in get_legal_columns(&state) {
+                let quick = evaluate_move_quick(&state, col, die, state.current_player, &config);
+                assert_eq!(order_score(&state, col, die, state.current_player) as f64, quick);
+            }
+        }
     }
     
-    let mut ctx = MCTSContext::new(player, time_budget_ms);
+    /// Timing of the table-based ordering against the quick evaluation it
+    /// replaced: `cargo test --release -- --ignored --nocapture bench_move_ordering`
+    #[test]
+    #[ignore]
+    fn bench_move_ordering() {
+        let positions = random_positions(9, 10_000);
+        let config = DifficultyConfig::default();
+        let time = |label: &str, score: &dyn Fn(&GameState, usize, u8) -> f64| {
+            let start = std::time::Instant::now();
+            let mut total = 0.0;
+            for _ in 0..20 {
+                for state in &positions {
+                    let die = state.current_die.unwrap();
+                    for col in 0..3 {
+                        total += score(state, col, die);
+                    }
+                }
+            }
+            println!("{label}: {:?} (checksum {total})", start.elapsed());
+        };
+        time("evaluate_move_quick", &|state, col, die| evaluate_move_quick(state, col, die, state.current_player, &config));
+        time("order_score", &|state, col, die| order_score(state, col, die, state.current_player) as f64);
+    }
     
-    // Expand root
-    mcts_expand(&mut ctx.root, state);
+    #[test]
+    fn end_game_summary_covers_only_the_last_game() {
+        let mut profile = OpponentProfile::new();
+        assert_eq!(profile.end_game_summary(), "null");
+        let mut play = |moves: &[(u8, u8, u32)]| {
+            for &(col, removed, score_lost) in moves {
+                profile.record_move(col, 4, removed, score_lost);
+            }
+            profile.end_game();
+            profile.end_game_summary()
+        };
+        
+        // An all-out attacker in column 0, then a quiet game in column 2
+        let first = play(&[(0, 1, 5); 12]);
+        assert!(first.starts_with("{\"moves\":12,\"attack_rate\":1.000,\"most_used_column\":0,\"points_removed\":60,"), "{first}");
+        assert!(first.ends_with("\"model_shifted\":false}"), "{first}");
+        let second = play(&[(2, 0, 0); 10]);
+        assert!(second.starts_with("{\"moves\":10,\"attack_rate\":0.000,\"most_used_column\":2,\"points_removed\":0,"), "{second}");
+        assert!(second.ends_with("\"personality\":\"balanced\",\"model_shifted\":false}"), "{second}");
+        
+        // The third game brings enough history to adapt to the attacks, which
+        // shifts the weights away from the balanced ones the game started with
+        let third = play(&[(1, 1, 3), (1, 1, 3), (1, 0, 0), (1, 0, 0), (0, 0, 0)]);
+        assert!(third.starts_with("{\"moves\":5,\"attack_rate\":0.400,\"most_used_column\":1,\"points_removed\":6,"), "{third}");
+        assert!(third.ends_with("\"personality\":\"defensive\",\"model_shifted\":true}"), "{third}");
+        // A game with no moves leaves the weights where they were
+        let empty = play(&[]);
+        assert!(empty.starts_with("{\"moves\":0,\"attack_rate\":0.000,\"most_used_column\":-1,\"points_removed\":0,"), "{empty}");
+        assert!(empty.ends_with("\"personality\":\"defensive\",\"model_shifted\":false}"), "{empty}");
+    }
     
-    // Run simulations until time budget is exhausted
-    while !ctx.should_stop() {
-        mcts_iterate(&mut ctx.root, state, player);
-        ctx.simulations += 1;
+    #[test]
+    fn opponent_decisions_are_not_cached_past_their_budget() {
+        let config = DifficultyConfig { depth: 4, advanced_eval: true, ..DifficultyConfig::default() };
+        let opponent = DifficultyConfig { depth: 3, ..config };
+        for state in random_positions(2, 4) {
+            let player = state.current_player;
+            let mut fresh = test_ctx();
+            let expected = expectimax_internal(&state, player, &config, &opponent, &mut fresh);
+            
+            // Starved opponent model: its decisions rest on truncated searches
+            let mut ctx = test_ctx();
+            ctx.opponent_node_share = 0.0005;
+            expectimax_internal(&state, player, &config, &opponent, &mut ctx);
+            assert!(ctx.opponent_nodes as f64 > ctx.max_nodes as f64 * ctx.opponent_node_share);
+            ctx.opponent_node_share = DEFAULT_OPPONENT_NODE_SHARE;
+            restart(&mut ctx);
+            assert_eq!(expectimax_internal(&state, player, &config, &opponent, &mut ctx), expected);
+        }
     }
     
-    // Select best action by visit count
-    ctx.root.best_action_by_visits()
-}
-
-// Add MCTS method to AIEngine
-#[wasm_bindgen]
-impl AIEngine {
-    /// Get the best move using MCTS (Monte Carlo Tree Search)
-    #[wasm_bindgen]
-    pub fn get_mcts_move(
-        &mut self,
-        grid1: &[u8],
-        grid2: &[u8],
-        current_player: u8,
-        current_die: u8,
-        time_budget_ms: f64,
-    ) -> i32 {
-        // Convert from JS arrays to GameState
-        let mut state = GameState {
-            grid1: Grid { data: [0; 9] },
-            grid2: Grid { data: [0; 9] },
-            current_player: if current_player == 0 { Player::Player1 } else { Player::Player2 },
-            current_die: if current_die == 0 { None } else { Some(current_die) },
-            phase: if current_die == 0 { GamePhase::Rolling } else { GamePhase::Placing },
-            turn_number: 1,
-        };
+    #[test]
+    fn reused_opponent_decisions_save_nodes() {
+        // Mirrored mid-game position: both sides hold the same dice
+        let grid = [3, 1, 0, 5, 0, 0, 2, 6, 0];
+        let state = state_from_js(&grid, &grid, 0, 4);
+        let config = DifficultyConfig { depth: 5, advanced_eval: true, ..DifficultyConfig::default() };
+        let opponent = DifficultyConfig { depth: 2, ..config };
+        let mut ctx = test_ctx();
+        let (col, value) = expectimax_internal(&state, Player::Player1, &config, &opponent, &mut ctx);
+        // Each reuse is an opponent search skipped, and the value still
+        // matches one that decides every opponent turn afresh
+        assert!(ctx.counters.opponent_decisions_reused > 0);
+        let reference = reference_root_values(&state, &config, &opponent, false);
+        let (best_col, best_value) = reference.iter().copied().fold((0, f64::NEG_INFINITY), |best, entry| if entry.1 > best.1 { entry } else { best });
+        assert!((value - best_value).abs() < 1e-9, "{value} vs {reference:?}");
+        assert_eq!(col, Some(best_col));
+    }
+    
+    #[test]
+    fn diff_grids_finds_removed_and_shifted_cells() {
+        // Player2 plays a 4 into column 1, taking both of Player1's 4s there
+        let grid1 = [2, 0, 0, 4, 3, 4, 6, 0, 0];
+        let grid2 = [1, 0, 0, 5, 0, 0, 0, 0, 0];
+        let state = state_from_js(&grid1, &grid2, 1, 4);
+        let after = apply_move(&state, 1).unwrap();
+        assert_eq!(after.grid1.data, [2, 0, 0, 3, 0, 0, 6, 0, 0]);
         
-        // Copy grid data
-        let len1 = grid1.len().min(9);
-        for i in 0..len1 {
-            state.grid1.data[i] = grid1[i];
+        let engine = AIEngine::new();
+        assert_eq!(
+            engine.diff_grids(&grid1, &after.grid1.data),
+            // The 4 in cell 3 goes, the 3 slides down from cell 4, the 4 in cell 5 goes
+            vec![DIFF_REMOVED, 3, 4, DIFF_SHIFTED, 3, 4, DIFF_REMOVED, 5, 4]
+        );
+        assert_eq!(engine.diff_grids(&grid2, &after.grid2.data), vec![DIFF_PLACED, 4, 4]);
+        assert!(engine.diff_grids(&grid1, &grid1).is_empty());
+    }
+    
+    #[wasm_bindgen_test]
+    fn absolute_advantage_favors_player1_whoever_moves() {
+        let grid1 = [6, 6, 0, 5, 5, 0, 4, 0, 0];
+        let grid2 = [1, 0, 0, 2, 0, 0, 3, 0, 0];
+        let mut engine = AIEngine::new();
+        for player in 0..2 {
+            for die in [0, 2, 6] {
+                let advantage = engine.absolute_advantage(&grid1, &grid2, player, die, 2);
+                assert!(advantage > 5.0, "player {player} die {die}: {advantage}");
+            }
         }
-        for i in len1..9 {
-            state.grid1.data[i] = 0;
+        // Swapping the grids hands the same lead to Player2
+        for player in 0..2 {
+            assert!(engine.absolute_advantage(&grid2, &grid1, player, 0, 2) < -5.0);
         }
-        let len2 = grid2.len().min(9);
-        for i in 0..len2 {
-            state.grid2.data[i] = grid2[i];
+    }
+    
+    #[test]
+    fn root_lmr_spends_a_small_budget_on_the_top_moves() {
+        let config = DifficultyConfig { depth: 4, advanced_eval: true, ..DifficultyConfig::default() };
+        let opponent = DifficultyConfig { depth: 3, ..config };
+        let (mut lmr_total, mut flat_total, mut reduced) = (0.0, 0.0, 0);
+        for state in random_positions(11, 12) {
+            let player = state.current_player;
+            let mut chosen = [0; 2];
+            for (slot, lmr) in [false, true].into_iter().enumerate() {
+                let mut ctx = test_ctx();
+                ctx.max_nodes = 3000;
+                ctx.root_lmr = lmr;
+                chosen[slot] = expectimax_internal(&state, player, &config, &opponent, &mut ctx).0.unwrap();
+                if lmr {
+                    reduced += ctx.counters.reduced_root_moves;
+                }
+            }
+            // Score both choices with a complete search of the same depth
+            for (col, total) in [(chosen[0], &mut flat_total), (chosen[1], &mut lmr_total)] {
+                let next = apply_move(&state, col).unwrap();
+                *total += chance_node(&next, config.depth - 1, player, &config, &opponent, &mut test_ctx());
+            }
         }
-        for i in len2..9 {
-            state.grid2.data[i] = 0;
+        assert!(reduced > 0);
+        assert!(lmr_total >= flat_total - 1e-9, "{lmr_total} vs {flat_total}");
+    }
+    
+    #[test]
+    fn root_lmr_is_off_under_a_large_budget() {
+        let config = DifficultyConfig { depth: 4, advanced_eval: true, ..DifficultyConfig::default() };
+        let opponent = DifficultyConfig { depth: 3, ..config };
+        for state in random_positions(11, 4) {
+            let mut ctx = test_ctx();
+            ctx.root_lmr = true;
+            expectimax_internal(&state, state.current_player, &config, &opponent, &mut ctx);
+            assert_eq!(ctx.counters.reduced_root_moves, 0);
         }
+    }
+    
+    #[test]
+    fn removal_probability_is_zero_behind_a_full_column() {
+        let engine = AIEngine::new();
+        let mine = [5, 0, 0, 5, 0, 0, 0, 0, 0];
+        let open = [1, 2, 0, 0, 0, 0, 0, 0, 0];
+        let full = [1, 2, 3, 0, 0, 0, 0, 0, 0];
+        assert!((engine.removal_probability(&mine, &open, 0, 0) - 1.0 / 6.0).abs() < 1e-12);
+        assert_eq!(engine.removal_probability(&mine, &full, 0, 0), 0.0);
+        // Only the matching column is blocked, and an empty cell is never at risk
+        assert!(engine.removal_probability(&mine, &full, 1, 0) > 0.0);
+        assert_eq!(engine.removal_probability(&mine, &full, 2, 0), 0.0);
+    }
+    
+    #[test]
+    fn solve_known_sequence_captures_then_stacks() {
+        let engine = AIEngine::new();
+        let grid2 = [0, 0, 0, 6, 6, 0, 0, 0, 0];
+        // The first 6 clears the opponent's pair, the second doubles up on it
+        assert_eq!(engine.solve_known_sequence(&[0; 9], &grid2, 0, &[6, 6]), vec![1, 1]);
+        // Wherever a leading 2 goes, the 6 still takes the capture
+        let plan = engine.solve_known_sequence(&[0; 9], &grid2, 0, &[2, 6]);
+        assert_eq!(plan.len(), 2);
+        assert_eq!(plan[1], 1);
+        assert_eq!(engine.solve_known_sequence(&[0; 9], &grid2, 0, &[0]), Vec::<u8>::new());
+    }
+    
+    #[test]
+    fn average_die_is_tracked_per_column() {
+        let profile = profile_with_moves(&[(0, 2, 1), (0, 4, 1), (2, 6, 3)]);
+        assert_eq!(profile.get_average_die(0), 3.0);
+        assert_eq!(profile.get_average_die(1), 0.0);
+        assert_eq!(profile.get_average_die(2), 6.0);
+        assert_eq!(profile.get_average_die(3), 0.0);
+    }
+    
+    #[test]
+    fn version_1_blob_imports_with_default_column_stats() {
+        let mut source = profile_with_moves(&[(0, 2, 2), (1, 5, 3)]);
+        source.record_move(2, 6, 1, 6);
+        source.end_game();
+        // A version 1 blob is the current layout cut off before the per-column stats
+        let mut blob = source.export_full();
+        blob.truncate(1 + 4 * 13);
+        blob[0] = 1;
         
-        if state.phase != GamePhase::Placing || state.current_die.is_none() {
-            return -1;
+        let mut imported = OpponentProfile::new();
+        assert!(imported.import_full(&blob));
+        assert_eq!(imported.column_usage, source.column_usage);
+        assert_eq!(imported.total_moves, source.total_moves);
+        assert_eq!(imported.attack_moves, source.attack_moves);
+        assert_eq!(imported.high_dice_placements, source.high_dice_placements);
+        assert_eq!(imported.low_dice_placements, source.low_dice_placements);
+        assert_eq!(imported.score_lost_to_attacks, source.score_lost_to_attacks);
+        assert_eq!(imported.games_completed, 1);
+        for col in 0..3 {
+            assert_eq!(imported.get_average_die(col), 0.0);
+            assert_eq!(imported.get_score_lost_in_column(col), 0);
         }
         
-        match mcts_search(&state, time_budget_ms) {
-            Some(col) => col as i32,
-            None => -1,
-        }
+        // A truncated version 1 blob is rejected and leaves the profile alone
+        let mut untouched = OpponentProfile::new();
+        assert!(!untouched.import_full(&blob[..blob.len() - 1]));
+        assert_eq!(untouched.total_moves, 0);
     }
     
-    /// Get the best move using hybrid approach: MCTS with neural network priors
-    /// For now, uses uniform priors until policy network is trained
-    #[wasm_bindgen]
-    pub fn get_hybrid_move(
-        &mut self,
-        grid1: &[u8],
-        grid2: &[u8],
-        current_player: u8,
-        current_die: u8,
-        time_budget_ms: f64,
-        policy_weights: Option<Vec<f64>>,
-    ) -> i32 {
-        // For now, just use MCTS with uniform priors
-        // Policy weights will be used when we have a trained model
-        let _ = policy_weights;
-        self.get_mcts_move(grid1, grid2, current_player, current_die, time_budget_ms)
+    #[test]
+    fn basic_tiebreak_prefers_the_higher_score_on_a_tied_value() {
+        // Stacking the 4 in the middle and capturing with it on the right come
+        // out level on the advanced evaluation, but the capture scores more
+        let state = state_from_js(&[1, 4, 1, 6, 1, 0, 3, 6, 0], &[6, 4, 1, 5, 0, 0, 4, 2, 2], 0, 4);
+        let config = DifficultyConfig { depth: 1, advanced_eval: true, basic_tiebreak: true, ..DifficultyConfig::default() };
+        let (stack, capture) = (apply_move(&state, 1).unwrap(), apply_move(&state, 2).unwrap());
+        let tied = evaluate(&stack, Player::Player1, &config) - evaluate(&capture, Player::Player1, &config);
+        assert!(tied.abs() < ROOT_TIE_EPSILON, "{tied}");
+        assert!(evaluate_basic(&capture, Player::Player1) > evaluate_basic(&stack, Player::Player1));
+        assert_eq!(expectimax_internal(&state, Player::Player1, &config, &config, &mut test_ctx()).0, Some(2));
     }
-}
-
-// ============================================================================
-// Neural Network-Guided MCTS
-// ============================================================================
-
-/// Expand a node using neural network policy priors
-fn mcts_expand_with_nn(node: &mut MCTSNode, state: &GameState, network: &PolicyValueNetwork) {
-    if state.phase == GamePhase::Ended {
-        return;
+    
+    #[test]
+    fn shallow_stores_keep_a_deeper_entry() {
+        let mut ctx = test_ctx();
+        ctx.tt_store(42, TTEntry::value(5, 1.5));
+        ctx.tt_generation += 1;
+        ctx.tt_store(42, TTEntry::value(2, -3.0));
+        let kept = ctx.tt[&42];
+        assert_eq!((kept.depth, kept.value), (5, 1.5));
+        // The revisit still marks the deep entry as in use
+        assert_eq!(kept.generation, ctx.tt_generation);
+        // An equal or deeper result replaces it
+        ctx.tt_store(42, TTEntry::value(5, 2.5));
+        assert_eq!(ctx.tt[&42].value, 2.5);
+        ctx.tt_store(42, TTEntry::value(7, -1.0));
+        assert_eq!((ctx.tt[&42].depth, ctx.tt[&42].value), (7, -1.0));
+        
+        // Searching the same position again reuses the entries
+        let config = DifficultyConfig { depth: 4, advanced_eval: true, ..DifficultyConfig::default() };
+        let (grid1, grid2, player, die) = POSITIONS[0];
+        let state = state_from_js(&grid1, &grid2, player, die);
+        let mut ctx = test_ctx();
+        let first = expectimax_internal(&state, state.current_player, &config, &config, &mut ctx);
+        let (first_nodes, first_hits) = (ctx.nodes_explored, ctx.tt_hits);
+        restart(&mut ctx);
+        assert_eq!(expectimax_internal(&state, state.current_player, &config, &config, &mut ctx), first);
+        assert!(ctx.nodes_explored < first_nodes, "{} vs {first_nodes}", ctx.nodes_explored);
+        assert!(ctx.tt_hits > first_hits);
     }
     
-    let legal_columns = get_legal_columns(state);
-    if legal_columns.is_empty() {
-        return;
+    #[test]
+    fn audit_position_reports_each_corruption() {
+        use PositionProblem::*;
+        let engine = AIEngine::new();
+        let audit = |grid1: &[u8], grid2: &[u8], player: u8, die: u8, turn: u32| engine.audit_position(grid1, grid2, player, die, turn);
+        let codes = |problems: &[PositionProblem]| problems.iter().map(|&p| p as u8).collect::<Vec<u8>>();
+        
+        let sound = [3, 0, 0, 5, 2, 0, 0, 0, 0];
+        assert!(audit(&sound, &[0; 9], 0, 4, 4).is_empty());
+        assert_eq!(audit(&sound[..8], &[0; 9], 0, 4, 4), codes(&[GridLength]));
+        assert_eq!(audit(&[3, 0, 0, 5, 2, 0, 0, 0, 0, 1], &[0; 9], 0, 4, 5), codes(&[GridLength]));
+        assert_eq!(audit(&[7, 0, 0, 0, 0, 0, 0, 0, 0], &[0; 9], 0, 4, 2), codes(&[InvalidCellValue]));
+        assert_eq!(audit(&[0, 3, 0, 0, 0, 0, 0, 0, 0], &[0; 9], 0, 4, 2), codes(&[GravityViolation]));
+        assert_eq!(audit(&sound, &[0; 9], 0, 7, 4), codes(&[DieOutOfRange]));
+        assert_eq!(audit(&sound, &[0; 9], 2, 4, 4), codes(&[InvalidPlayer]));
+        assert_eq!(audit(&[1; 9], &[0; 9], 1, 4, 10), codes(&[DieAfterGameEnd]));
+        assert_eq!(audit(&sound, &[0; 9], 0, 4, 3), codes(&[TurnNumberMismatch]));
+        assert_eq!(audit(&sound, &[0; 9], 0, 4, 0), codes(&[TurnNumberMismatch]));
+        // Several problems at once are each reported once, in check order
+        assert_eq!(audit(&[0, 9, 0, 0, 0, 0, 0, 0, 0], &[0, 8, 0, 0, 0, 0, 0, 0, 0], 3, 9, 1),
+            codes(&[InvalidCellValue, GravityViolation, DieOutOfRange, InvalidPlayer, TurnNumberMismatch]));
+    }
+    
+    #[test]
+    fn lerp_returns_each_endpoint_exactly() {
+        let a = DifficultyConfig {
+            randomness: 0.3,
+            symmetric_positional: true,
+            min_depth: 1,
+            ..DifficultyConfig::beginner()
+        };
+        let b = DifficultyConfig {
+            offense_weight: 0.35,
+            defense_weight: 0.65,
+            basic_tiebreak: true,
+            min_depth: 3,
+            ..DifficultyConfig::master()
+        };
+        assert_eq!(DifficultyConfig::lerp(&a, &b, 0.0), a);
+        assert_eq!(DifficultyConfig::lerp(&a, &b, 1.0), b);
+        // Out-of-range and non-finite knobs clamp to an endpoint
+        assert_eq!(DifficultyConfig::lerp(&a, &b, -2.0), a);
+        assert_eq!(DifficultyConfig::lerp(&a, &b, 3.0), b);
+        assert_eq!(DifficultyConfig::lerp(&a, &b, f64::NAN), a);
+        
+        let mid = DifficultyConfig::lerp(&a, &b, 0.5);
+        assert!((mid.offense_weight - 0.525).abs() < 1e-12);
+        assert_eq!(mid.min_depth, 2);
+        assert_eq!(mid.advanced_eval, b.advanced_eval);
     }
     
-    // Get policy priors from network
-    let policy_probs = network.get_policy_for_legal(state, &legal_columns);
+    #[wasm_bindgen_test]
+    fn branching_factor_stays_within_the_game_tree_bounds() {
+        for (grid1, grid2, player, die) in POSITIONS {
+            let mut engine = AIEngine::new();
+            engine.get_best_move(&grid1, &grid2, player, die, 3, 0.0, 0.5, 0.5, false, 3, 0.0, 0.5, 0.5, false);
+            let stats = engine.last_search_stats();
+            let branching = stats.get_branching_factor();
+            // A ply is at most three placements, each followed by six rolls
+            assert!((1.0..=18.0).contains(&branching), "{branching}");
+            let nodes = branching.powi(stats.get_depth_reached() as i32);
+            assert!((nodes - stats.get_nodes() as f64).abs() < 1e-6 * nodes);
+            
+            engine.get_best_move(&grid1, &grid2, player, die, 0, 0.0, 0.5, 0.5, false, 0, 0.0, 0.5, 0.5, false);
+            assert_eq!(engine.last_search_stats().get_branching_factor(), 0.0);
+        }
+    }
     
-    for (col, prior) in policy_probs {
-        if !node.children.contains_key(&col) {
-            let next_player = match state.current_player {
-                Player::Player1 => Player::Player2,
-                Player::Player2 => Player::Player1,
-            };
-            node.children.insert(col, MCTSNode::new(prior, next_player));
+    #[test]
+    fn fill_grid_random_leaves_the_requested_gaps_under_gravity() {
+        let mut engine = AIEngine::new();
+        for faces in [6, 8] {
+            engine.set_die_faces(faces);
+            for seed in 0..20 {
+                for empties in 0..=9 {
+                    let cells = engine.fill_grid_random(seed, empties);
+                    assert_eq!(cells.len(), 9);
+                    assert_eq!(cells.iter().filter(|&&v| v == 0).count(), empties as usize);
+                    assert!(cells.iter().all(|&v| v <= faces));
+                    assert!(Grid::from_slice(&cells).respects_gravity());
+                }
+                assert_eq!(engine.fill_grid_random(seed, 4), engine.fill_grid_random(seed, 4));
+            }
         }
+        assert!(engine.fill_grid_random(1, 10).is_empty());
     }
-}
-
-/// Run one MCTS iteration with neural network guidance
-fn mcts_iterate_with_nn(
-    node: &mut MCTSNode,
-    state: &GameState,
-    root_player: Player,
-    network: &PolicyValueNetwork,
-) -> f64 {
-    node.visits += 1;
     
-    // Terminal state
-    if state.phase == GamePhase::Ended {
-        let value = evaluate_normalized(state, root_player);
-        node.is_terminal = true;
-        node.terminal_value = value;
-        node.total_value += value;
-        return value;
+    /// Plain expectimax with no pruning, table or budget, mirroring the search's
+    /// semantics for a deterministic opponent model (no randomness, full skill,
+    /// no blunders): the reference the pruned search must agree with
+    fn reference_value(
+        state: &GameState,
+        depth: u32,
+        player: Player,
+        player_config: &DifficultyConfig,
+        opponent_config: &DifficultyConfig,
+        adversarial: bool,
+    ) -> f64 {
+        if state.phase == GamePhase::Rolling {
+            if depth == 0 {
+                return evaluate(state, player, player_config);
+            }
+            // Weighted the way `chance_node_window` sums them, so the modeled
+            // opponent's near-tied replies round the same way and it picks the same one
+            return (1..=6)
+                .map(|die| reference_value(&roll_die(state, die), depth, player, player_config, opponent_config, adversarial) * (1.0 / 6.0))
+                .sum();
+        }
+        let legal = get_legal_columns(state);
+        if state.phase == GamePhase::Ended || depth == 0 || legal.is_empty() {
+            return evaluate(state, player, player_config);
+        }
+        let child = |col: usize| apply_move_for(state, col, player_config).unwrap();
+        let value_of = |next: &GameState| reference_value(next, depth - 1, player, player_config, opponent_config, adversarial);
+        if state.current_player == player {
+            return legal.iter()
+                .map(|&col| value_of(&child(col)) + column_bias(player_config, col))
+                .fold(f64::NEG_INFINITY, f64::max);
+        }
+        if adversarial {
+            return legal.iter().map(|&col| value_of(&child(col))).fold(f64::INFINITY, f64::min);
+        }
+        let opponent = state.current_player;
+        let col = if opponent_config.depth == 0 {
+            // The greedy reply: the first of the best quick-scored moves
+            order_moves(state, &legal, opponent)[0]
+        } else {
+            let limited = DifficultyConfig { depth: opponent_config.depth.min(depth), ..*opponent_config };
+            let mut best = (legal[0], f64::NEG_INFINITY);
+            for col in order_moves(state, &legal, opponent) {
+                let value = reference_value(&child(col), limited.depth - 1, opponent, &limited, player_config, false);
+                if value > best.1 {
+                    best = (col, value);
+                }
+            }
+            best.0
+        };
+        value_of(&child(col))
     }
     
-    // Handle chance nodes (rolling phase) - sample a die
-    if state.phase == GamePhase::Rolling {
-        let die_value = (js_sys::Math::random() * 6.0) as u8 + 1;
-        let rolled_state = roll_die(state, die_value);
-        let value = mcts_iterate_with_nn(node, &rolled_state, root_player, network);
-        node.total_value += value;
-        return value;
+    /// Each root move's reference value, as `expectimax_internal` compares them
+    fn reference_root_values(
+        state: &GameState,
+        player_config: &DifficultyConfig,
+        opponent_config: &DifficultyConfig,
+        adversarial: bool,
+    ) -> Vec<(usize, f64)> {
+        get_legal_columns(state).into_iter()
+            .map(|col| {
+                let next = apply_move_for(state, col, player_config).unwrap();
+                (col, reference_value(&next, player_config.depth - 1, state.current_player, player_config, opponent_config, adversarial))
+            })
+            .collect()
     }
     
-    // Expand if not expanded
-    if node.children.is_empty() {
-        mcts_expand_with_nn(node, state, network);
+    #[test]
+    fn deep_self_search_plans_ahead_against_a_greedy_opponent() {
+        let greedy = DifficultyConfig { depth: 0, ..DifficultyConfig::default() };
+        let best = |values: &[(usize, f64)]| values.iter().map(|&(_, value)| value).fold(f64::NEG_INFINITY, f64::max);
+        // The search sees the greedy replies all the way down, several own turns deep
+        for depth in [3, 4] {
+            let deep = DifficultyConfig { depth, ..DifficultyConfig::default() };
+            for state in random_positions(5, 6) {
+                let (col, value) = expectimax_internal(&state, state.current_player, &deep, &greedy, &mut test_ctx());
+                let reference = reference_root_values(&state, &deep, &greedy, false);
+                assert!((value - best(&reference)).abs() < 1e-9, "depth {depth}: {value} vs {reference:?}");
+                assert!(col.is_some());
+            }
+        }
         
-        if node.children.is_empty() {
-            let value = network.get_value(state);
-            // Convert to root player's perspective
-            let adjusted_value = if state.current_player == root_player {
-                value
-            } else {
-                -value
-            };
-            node.total_value += adjusted_value;
-            return adjusted_value;
+        // Finishing the middle column sets up the next turn against a greedy
+        // reply, though a one-ply look sees nothing to choose between the
+        // columns and a minimizing opponent would punish it
+        let state = state_from_js(&[3, 4, 0, 2, 3, 0, 1, 2, 0], &[6, 0, 0, 6, 0, 0, 1, 0, 0], 0, 5);
+        let deep = DifficultyConfig { depth: 3, ..DifficultyConfig::default() };
+        assert_eq!(expectimax_internal(&state, Player::Player1, &deep, &greedy, &mut test_ctx()).0, Some(1));
+        assert_eq!(expectimax_internal(&state, Player::Player1, &depth_config(1), &greedy, &mut test_ctx()).0, Some(0));
+        let mut adversarial = test_ctx();
+        adversarial.use_adversarial = true;
+        assert_eq!(expectimax_internal(&state, Player::Player1, &deep, &deep, &mut adversarial).0, Some(2));
+        let against_greedy = reference_root_values(&state, &deep, &greedy, false);
+        assert!(against_greedy.iter().all(|&(col, value)| col == 1 || value < against_greedy[1].1 - 0.25));
+    }
+    
+    #[test]
+    fn depth_one_against_a_greedy_opponent_scores_the_placement_alone() {
+        let greedy = DifficultyConfig { depth: 0, ..DifficultyConfig::default() };
+        let shallow = DifficultyConfig { depth: 1, advanced_eval: true, ..DifficultyConfig::default() };
+        for state in random_positions(8, 8) {
+            let player = state.current_player;
+            // With no plies left after the placement, the greedy reply never runs
+            let placements: Vec<(usize, f64)> = get_legal_columns(&state).into_iter()
+                .map(|col| (col, evaluate(&apply_move(&state, col).unwrap(), player, &shallow)))
+                .collect();
+            let (col, value) = expectimax_internal(&state, player, &shallow, &greedy, &mut test_ctx());
+            if placements.len() > 1 {
+                assert_eq!(placements.iter().find(|&&(c, _)| Some(c) == col).unwrap().1, value);
+                assert!(placements.iter().all(|&(_, other)| other <= value));
+            }
+            assert_eq!(reference_root_values(&state, &shallow, &greedy, false), placements);
+        }
+    }
+    
+    #[test]
+    fn states_key_a_hash_map_by_position() {
+        let mut seen: HashMap<GameState, u32> = HashMap::new();
+        let positions = random_positions(3, 6);
+        for (idx, state) in positions.iter().enumerate() {
+            seen.insert(state.clone(), idx as u32);
         }
+        let distinct = seen.len();
         
-        // Use network value instead of rollout
-        let value = network.get_value(state);
-        let adjusted_value = if state.current_player == root_player {
-            value
-        } else {
-            -value
-        };
+        // The same position later in the game lands on the same key
+        for state in &positions {
+            let later = GameState { turn_number: state.turn_number + 12, ..state.clone() };
+            let earlier = seen.get(state).copied();
+            assert!(earlier.is_some());
+            assert_eq!(seen.insert(later, 99), earlier);
+        }
+        assert_eq!(seen.len(), distinct);
         
-        // Still need to propagate to children
-        if let Some(action) = node.select_child() {
-            if let Some(child) = node.children.get_mut(&action) {
-                child.visits += 1;
-                child.total_value += adjusted_value;
-            }
+        // Any difference in the position itself is a new key
+        let base = positions[0].clone();
+        let mut grid = base.grid1.clone();
+        grid.data[8] = if grid.data[8] == 0 { 1 } else { 0 };
+        let other_player = match base.current_player { Player::Player1 => Player::Player2, Player::Player2 => Player::Player1 };
+        let variants = [
+            GameState { grid1: grid, ..base.clone() },
+            GameState { current_player: other_player, ..base.clone() },
+            GameState { current_die: None, phase: GamePhase::Rolling, ..base.clone() },
+        ];
+        for variant in variants {
+            assert!(seen.insert(variant, 0).is_none());
         }
+        assert_eq!(seen.len(), distinct + 3);
         
-        node.total_value += adjusted_value;
-        return adjusted_value;
+        let mut grids = HashMap::new();
+        grids.insert(Grid::from_slice(&[1, 2, 0, 0, 0, 0, 0, 0, 0]), 1);
+        assert_eq!(grids.get(&Grid::from_slice(&[1, 2])), Some(&1));
+        assert_eq!(grids.get(&Grid::from_slice(&[2, 1])), None);
     }
     
-    // Selection - pick best child via PUCT
-    if let Some(action) = node.select_child() {
-        if let Some(new_state) = apply_move(state, action) {
-            if let Some(child) = node.children.get_mut(&action) {
-                let value = mcts_iterate_with_nn(child, &new_state, root_player, network);
-                node.total_value += value;
-                return value;
-            }
-        }
+    #[test]
+    fn all_winning_moves_lists_every_forced_win() {
+        let engine = AIEngine::new();
+        // Player2 has one cell left, so their next placement ends a game they trail
+        let (grid1, grid2) = ([6, 6, 0, 5, 5, 0, 6, 6, 6], [1, 2, 3, 2, 3, 4, 1, 2, 0]);
+        assert_eq!(engine.all_winning_moves(&grid1, &grid2, 0, 5, 2), vec![0, 1]);
+        // A 4 in the middle captures, handing Player2 room to play on
+        assert_eq!(engine.all_winning_moves(&grid1, &grid2, 0, 4, 2), vec![0]);
+        assert!(engine.all_winning_moves(&grid1, &grid2, 0, 5, 0).is_empty());
+        // The side that trails has nothing to force
+        assert!(engine.all_winning_moves(&grid2, &grid1, 0, 5, 2).is_empty());
     }
     
-    // Fallback
-    let value = network.get_value(state);
-    let adjusted_value = if state.current_player == root_player {
-        value
-    } else {
-        -value
-    };
-    node.total_value += adjusted_value;
-    adjusted_value
-}
-
-/// Run neural network-guided MCTS search
-fn mcts_search_with_nn(
-    state: &GameState, 
-    time_budget_ms: f64,
-    network: &PolicyValueNetwork,
-) -> Option<usize> {
-    if state.phase != GamePhase::Placing || state.current_die.is_none() {
-        return None;
+    #[test]
+    fn waste_weight_keeps_a_six_out_of_a_dead_end_column() {
+        // A 6 scores 6 on the left or in the middle; on the left it can never pair
+        let state = state_from_js(&[2, 3, 0, 0, 0, 0, 1, 2, 3], &[1, 0, 0, 2, 0, 0, 0, 0, 0], 0, 6);
+        let legal = get_legal_columns(&state);
+        let plain = DifficultyConfig { depth: 0, ..DifficultyConfig::default() };
+        let careful = DifficultyConfig { high_die_waste_weight: 1.0, ..plain };
+        assert_eq!(evaluate_move_quick(&state, 0, 6, Player::Player1, &plain), evaluate_move_quick(&state, 1, 6, Player::Player1, &plain));
+        assert_eq!(greedy_column(&state, &legal, Player::Player1, &plain), Some(0));
+        assert_eq!(greedy_column(&state, &legal, Player::Player1, &careful), Some(1));
+        
+        let advanced = DifficultyConfig { depth: 1, advanced_eval: true, high_die_waste_weight: 1.0, ..DifficultyConfig::default() };
+        assert_eq!(expectimax_internal(&state, Player::Player1, &advanced, &advanced, &mut test_ctx()).0, Some(1));
+        
+        // With only dead-end columns left the 6 still gets placed
+        let cramped = state_from_js(&[2, 3, 0, 1, 2, 3, 1, 2, 3], &[0; 9], 0, 6);
+        assert_eq!(greedy_column(&cramped, &get_legal_columns(&cramped), Player::Player1, &careful), Some(0));
     }
     
-    let player = state.current_player;
-    let legal_columns = get_legal_columns(state);
+    #[wasm_bindgen_test]
+    fn eval_without_cell_values_removing_a_pair() {
+        let (grid1, grid2) = ([3, 0, 0, 5, 5, 0, 1, 0, 0], [6, 6, 0, 2, 0, 0, 4, 0, 0]);
+        let mut engine = AIEngine::new();
+        for player in 0..2u8 {
+            // Breaking up a pair helps the other side and hurts the owner
+            let (mine, theirs) = if player == 0 { ((0, 1), (1, 0)) } else { ((1, 0), (0, 1)) };
+            assert!(engine.eval_without_cell(&grid1, &grid2, player, theirs.0, theirs.1, 0, 2) > 5.0);
+            assert!(engine.eval_without_cell(&grid1, &grid2, player, mine.0, mine.1, 0, 2) < -5.0);
+        }
+        assert_eq!(engine.eval_without_cell(&grid1, &grid2, 0, 1, 2, 2, 2), 0.0);
+        assert!(engine.eval_without_cell(&grid1, &grid2, 2, 0, 0, 0, 2).is_nan());
+    }
     
-    if legal_columns.is_empty() {
-        return None;
+    #[test]
+    fn rolling_states_are_expanded_once_per_turn() {
+        let config = DifficultyConfig { depth: 3, advanced_eval: true, ..DifficultyConfig::default() };
+        let greedy = DifficultyConfig { depth: 0, ..config };
+        // Turns with depth left to search: each root move's rolling state, then
+        // the rolling state after each roll's greedy reply; the reply after that
+        // only reaches the horizon
+        let expected_turns = |state: &GameState| -> u32 {
+            get_legal_columns(state).into_iter()
+                .map(|col| apply_move(state, col).unwrap())
+                .filter(|next| next.phase == GamePhase::Rolling)
+                .map(|next| {
+                    let replies = (1..=6).filter(|&die| {
+                        let rolled = roll_die(&next, die);
+                        let legal = get_legal_columns(&rolled);
+                        let reply = greedy_column(&rolled, &legal, rolled.current_player, &greedy).unwrap();
+                        apply_move(&rolled, reply).unwrap().phase == GamePhase::Rolling
+                    });
+                    1 + replies.count() as u32
+                })
+                .sum()
+        };
+        for (grid1, grid2, player, die) in POSITIONS {
+            let state = state_from_js(&grid1, &grid2, player, die);
+            let mut ctx = test_ctx();
+            evaluate_root_moves(&state, state.current_player, &config, &greedy, &mut ctx);
+            assert_eq!(ctx.counters.chance_expansions, expected_turns(&state));
+        }
     }
     
-    if legal_columns.len() == 1 {
-        return Some(legal_columns[0]);
+    #[wasm_bindgen_test]
+    fn column_commitment_advice_backs_a_safe_pair_over_a_junk_column() {
+        let mut engine = AIEngine::new();
+        // A pair of 6s behind a full opposing column is worth building on
+        let (good, good_opp) = ([6, 6, 0, 1, 0, 0, 2, 0, 0], [1, 2, 3, 0, 0, 0, 0, 0, 0]);
+        assert!(engine.column_commitment_advice(&good, &good_opp, 0, 0, 2) > 0.5);
+        assert!(engine.column_commitment_advice(&good_opp, &good, 1, 0, 2) > 0.5);
+        // Spending dice on a mismatched column gives up the pairs elsewhere
+        let (bad, bad_opp) = ([1, 2, 0, 6, 6, 0, 5, 5, 0], [0; 9]);
+        assert!(engine.column_commitment_advice(&bad, &bad_opp, 0, 0, 2) < -1.0);
+        assert!(engine.column_commitment_advice(&bad_opp, &bad, 1, 0, 2) < -1.0);
+        assert!(engine.column_commitment_advice(&good_opp, &good, 0, 0, 2).is_nan());
+    }
+    
+    #[test]
+    fn solitaire_leaves_the_opponent_alone_and_maximizes_own_score() {
+        let solitaire = DifficultyConfig { depth: 1, solitaire: true, ..DifficultyConfig::default() };
+        for state in random_positions(4, 10) {
+            let player = state.current_player;
+            let own = |s: &GameState| calculate_grid_score(if player == Player::Player1 { &s.grid1 } else { &s.grid2 }) as f64;
+            let mut best = f64::NEG_INFINITY;
+            for col in get_legal_columns(&state) {
+                let next = apply_move_for(&state, col, &solitaire).unwrap();
+                match player {
+                    Player::Player1 => assert_eq!(next.grid2, state.grid2),
+                    Player::Player2 => assert_eq!(next.grid1, state.grid1),
+                }
+                best = best.max(own(&next));
+            }
+            let (col, value) = expectimax_internal(&state, player, &solitaire, &solitaire, &mut test_ctx());
+            if get_legal_columns(&state).len() > 1 {
+                assert_eq!(value, best);
+                assert_eq!(own(&apply_move_for(&state, col.unwrap(), &solitaire).unwrap()), best);
+            }
+        }
+        
+        // Capturing the pair of 6s would win the margin, but stacking scores more
+        let state = state_from_js(&[0, 0, 0, 6, 0, 0, 0, 0, 0], &[6, 6, 0, 0, 0, 0, 0, 0, 0], 0, 6);
+        let normal = DifficultyConfig { solitaire: false, ..solitaire };
+        assert_eq!(expectimax_internal(&state, Player::Player1, &normal, &normal, &mut test_ctx()).0, Some(0));
+        assert_eq!(expectimax_internal(&state, Player::Player1, &solitaire, &solitaire, &mut test_ctx()).0, Some(1));
+        let deep = DifficultyConfig { depth: 3, ..solitaire };
+        assert_eq!(expectimax_internal(&state, Player::Player1, &deep, &deep, &mut test_ctx()).0, Some(1));
     }
     
-    let mut ctx = MCTSContext::new(player, time_budget_ms);
+    #[test]
+    fn opponent_responses_lead_with_wiping_a_valuable_column() {
+        let engine = AIEngine::new();
+        // A 5 on their right wipes our pair of 5s, worth far more than a stack
+        let mine = [1, 0, 0, 2, 0, 0, 5, 5, 0];
+        let theirs = [5, 0, 0, 0, 0, 0, 0, 0, 0];
+        assert_eq!(engine.opponent_responses(&mine, &theirs, 5), vec![2, 0, 1]);
+        // Without the pair to hit, doubling up their own 5 comes first
+        assert_eq!(engine.opponent_responses(&[1, 0, 0, 2, 0, 0, 3, 0, 0], &theirs, 5), vec![0, 1, 2]);
+        // Full columns are not offered
+        assert_eq!(engine.opponent_responses(&mine, &[5, 1, 2, 0, 0, 0, 0, 0, 0], 5), vec![2, 1]);
+        assert!(engine.opponent_responses(&mine, &theirs, 0).is_empty());
+    }
     
-    // Expand root with network priors
-    mcts_expand_with_nn(&mut ctx.root, state, network);
+    #[wasm_bindgen_test]
+    fn tiny_budget_reports_an_unreliable_capped_move() {
+        let (grid1, grid2) = ([3, 0, 0, 0, 0, 0, 0, 0, 0], [2, 2, 0, 0, 0, 0, 3, 0, 0]);
+        let mut engine = AIEngine::new();
+        engine.set_max_nodes(150);
+        engine.get_best_move(&grid1, &grid2, 1, 5, 4, 0.0, 0.5, 0.5, true, 4, 0.0, 0.5, 0.5, true);
+        assert!(engine.last_search_stats().is_truncated());
+        // Without the check a capped search is taken on trust
+        assert!(engine.cap_reliability());
+        
+        engine.set_check_cap_reliability(true);
+        engine.get_best_move(&grid1, &grid2, 1, 5, 4, 0.0, 0.5, 0.5, true, 4, 0.0, 0.5, 0.5, true);
+        assert!(!engine.cap_reliability());
+        
+        engine.set_max_nodes(500_000);
+        engine.get_best_move(&grid1, &grid2, 1, 5, 4, 0.0, 0.5, 0.5, true, 4, 0.0, 0.5, 0.5, true);
+        assert!(engine.cap_reliability());
+    }
     
-    // Run simulations
-    while !ctx.should_stop() {
-        mcts_iterate_with_nn(&mut ctx.root, state, player, network);
-        ctx.simulations += 1;
+    #[test]
+    fn same_seed_rolls_the_same_dice() {
+        let rolls = |engine: &mut AIEngine| (0..40).map(|_| engine.next_die()).collect::<Vec<u8>>();
+        let (mut a, mut b) = (AIEngine::new(), AIEngine::new());
+        a.set_seed(42);
+        b.set_seed(42);
+        let first = rolls(&mut a);
+        assert_eq!(first, rolls(&mut b));
+        assert!(first.iter().all(|&die| (1..=6).contains(&die)));
+        // Reseeding restarts the sequence; another seed gives another one
+        a.set_seed(42);
+        assert_eq!(rolls(&mut a), first);
+        b.set_seed(43);
+        assert_ne!(rolls(&mut b), first);
     }
     
-    ctx.root.best_action_by_visits()
-}
-
-/// Hybrid AI Engine that combines MCTS with neural network
-#[wasm_bindgen]
-pub struct HybridAIEngine {
-    network: PolicyValueNetwork,
-}
-
-#[wasm_bindgen]
-impl HybridAIEngine {
-    #[wasm_bindgen(constructor)]
-    pub fn new() -> Self {
-        HybridAIEngine {
-            network: PolicyValueNetwork::new(),
-        }
+    #[test]
+    fn propagated_column_bias_plans_to_keep_exploiting_a_column() {
+        // The opponent keeps dropping high dice in column 0
+        let mut profile = profile_with_moves(&[(0, 6, 8), (0, 5, 4), (1, 3, 1), (2, 2, 1)]);
+        assert!(profile.column_attack_bonus(0) > 5.0);
+        let root_only = DifficultyConfig { depth: 3, ..profile.get_adaptive_config() };
+        assert_eq!(root_only.column_bias, [0.0; 3]);
+        profile.set_propagate_column_bias(true);
+        let propagated = DifficultyConfig { depth: 3, ..profile.get_adaptive_config() };
+        assert_eq!(propagated.column_bias[0], profile.column_attack_bonus(0));
+        
+        // Scored the way get_master_move scores its root moves
+        let opponent = DifficultyConfig { depth: 3, advanced_eval: true, adversarial: true, ..DifficultyConfig::default() };
+        let state = state_from_js(&[6, 2, 0, 0, 0, 0, 6, 6, 0], &[0; 9], 0, 6);
+        let master_pick = |config: &DifficultyConfig| {
+            let mut ctx = test_ctx();
+            ctx.use_adversarial = true;
+            evaluate_root_moves(&state, Player::Player1, config, &opponent, &mut ctx).into_iter()
+                .map(|(col, value)| (col, value + profile.column_attack_bonus(col as u8)))
+                .fold((0, f64::NEG_INFINITY), |best, entry| if entry.1 > best.1 { entry } else { best })
+                .0
+        };
+        // With the bonus on the root move alone, the 6 goes straight into
+        // column 0; carried through the search, it completes the right column
+        // and leaves column 0 open for the turns to come
+        assert_eq!(master_pick(&root_only), 0);
+        assert_eq!(master_pick(&propagated), 2);
+        
+        // Under the win-probability objective the points become a share of a
+        // probability, not a whole one
+        let win = DifficultyConfig { objective: SearchObjective::WinProbability, ..propagated };
+        assert_eq!(column_bias(&propagated, 0), propagated.column_bias[0]);
+        assert!((column_bias(&win, 0) - propagated.column_bias[0] / (4.0 * WIN_PROBABILITY_SCALE)).abs() < 1e-12);
+        assert!(column_bias(&win, 0) < 0.25);
     }
     
-    /// Load weights into the neural network
-    #[wasm_bindgen]
-    pub fn load_weights(&mut self, weights: &[f64]) -> bool {
-        self.network.load_weights(weights)
+    #[test]
+    fn value_counts_tally_a_known_grid() {
+        let cells = [6, 6, 2, 1, 0, 0, 6, 3, 0];
+        let mut expected = [0u8; MAX_DIE_FACES as usize + 1];
+        expected[1] = 1;
+        expected[2] = 1;
+        expected[3] = 1;
+        expected[6] = 3;
+        assert_eq!(Grid::from_slice(&cells).value_counts(), expected);
+        
+        let mut engine = AIEngine::new();
+        assert_eq!(engine.grid_value_counts(&cells), vec![1, 1, 1, 0, 0, 3]);
+        assert_eq!(engine.grid_value_counts(&[]), vec![0; 6]);
+        assert!(engine.grid_value_counts(&[7]).is_empty());
+        engine.set_die_faces(8);
+        assert_eq!(engine.grid_value_counts(&[8, 7, 0, 8, 0, 0, 0, 0, 0]), vec![0, 0, 0, 0, 0, 0, 1, 2]);
     }
     
-    /// Check if the network is initialized
-    #[wasm_bindgen]
-    pub fn is_network_initialized(&self) -> bool {
-        self.network.is_initialized()
+    #[test]
+    fn equal_move_epsilon_settles_near_ties_by_exposure() {
+        // All three columns come within a point of each other; the right one
+        // leaves the least for the opponent's next roll to capture
+        let state = state_from_js(&[2, 6, 0, 2, 0, 0, 0, 0, 0], &[4, 0, 0, 0, 0, 0, 2, 0, 0], 0, 2);
+        let config = DifficultyConfig { depth: 2, ..DifficultyConfig::default() };
+        let values = reference_root_values(&state, &config, &config, false);
+        let best = values.iter().map(|&(_, value)| value).fold(f64::NEG_INFINITY, f64::max);
+        assert!(values.len() == 3 && values.iter().all(|&(_, value)| best - value <= 1.0 + 1e-9));
+        let exposure: Vec<f64> = (0..3).map(|col| capture_exposure(&apply_move(&state, col).unwrap(), Player::Player1, 6)).collect();
+        assert!(exposure[2] < exposure[0] && exposure[2] < exposure[1]);
+        
+        assert_eq!(expectimax_internal(&state, Player::Player1, &config, &config, &mut test_ctx()).0, Some(0));
+        let settled = DifficultyConfig { equal_move_epsilon: 1.5, ..config };
+        assert_eq!(expectimax_internal(&state, Player::Player1, &settled, &settled, &mut test_ctx()).0, Some(2));
+        // A window too narrow to reach the right column leaves a tie between
+        // the other two, and they expose the same
+        let narrow = DifficultyConfig { equal_move_epsilon: 0.5, ..config };
+        assert_eq!(expectimax_internal(&state, Player::Player1, &narrow, &narrow, &mut test_ctx()).0, Some(0));
     }
     
-    /// Get the weight count for the network
-    #[wasm_bindgen]
-    pub fn get_weight_count(&self) -> usize {
-        self.network.get_weight_count()
+    #[test]
+    fn loaded_threat_raises_pressure() {
+        let engine = AIEngine::new();
+        // Player2 has room to drop a 5 on Player1's pair of 5s
+        let (grid1, grid2) = ([5, 5, 0, 0, 0, 0, 0, 0, 0], [1, 0, 0, 0, 0, 0, 0, 0, 0]);
+        let pressure = engine.pressure(&grid1, &grid2);
+        assert!((pressure[0] - 1.0 / 6.0).abs() < 1e-12);
+        assert!((pressure[1] - 20.0 / 6.0).abs() < 1e-12);
+        // Filling the column in front of the pair takes the threat away
+        let blocked = engine.pressure(&grid1, &[1, 2, 3, 0, 0, 0, 0, 0, 0]);
+        assert_eq!(blocked[1], 0.0);
+        assert!(engine.pressure(&[7], &grid2).is_empty());
     }
     
-    /// Get the best move using neural network-guided MCTS
-    #[wasm_bindgen]
-    pub fn get_move(
-        &self,
-        grid1: &[u8],
-        grid2: &[u8],
-        current_player: u8,
-        current_die: u8,
-        time_budget_ms: f64,
-    ) -> i32 {
-        let mut state = GameState {
-            grid1: Grid { data: [0; 9] },
-            grid2: Grid { data: [0; 9] },
-            current_player: if current_player == 0 { Player::Player1 } else { Player::Player2 },
-            current_die: if current_die == 0 { None } else { Some(current_die) },
-            phase: if current_die == 0 { GamePhase::Rolling } else { GamePhase::Placing },
-            turn_number: 1,
+    #[test]
+    fn tempo_only_settles_near_equal_moves() {
+        let plain = DifficultyConfig { depth: 2, ..DifficultyConfig::default() };
+        let tempo = DifficultyConfig { tempo_bonus: 1.0, ..plain };
+        let pick = |state: &GameState, config: &DifficultyConfig| {
+            let mut ctx = test_ctx();
+            ctx.use_adversarial = true;
+            expectimax_internal(state, state.current_player, config, config, &mut ctx).0.unwrap()
+        };
+        let plain_values = |state: &GameState| {
+            let mut ctx = test_ctx();
+            ctx.use_adversarial = true;
+            evaluate_root_moves(state, state.current_player, &plain, &plain, &mut ctx)
         };
+        let value_of = |values: &[(usize, f64)], col: usize| values.iter().find(|&&(c, _)| c == col).unwrap().1;
         
-        let len1 = grid1.len().min(9);
-        for i in 0..len1 {
-            state.grid1.data[i] = grid1[i];
-        }
-        for i in len1..9 {
-            state.grid1.data[i] = 0;
+        // The 2 comes out within a third of a point in either open column, and
+        // the bonus settles it
+        let close = state_from_js(&[6, 6, 6, 5, 3, 0, 6, 0, 0], &[5, 2, 0, 5, 3, 2, 5, 1, 1], 0, 2);
+        let values = plain_values(&close);
+        assert_eq!(pick(&close, &plain), 2);
+        assert_eq!(pick(&close, &tempo), 1);
+        assert!(value_of(&values, 2) - value_of(&values, 1) < tempo.tempo_bonus, "{values:?}");
+        
+        // Capturing the pair of 6s is clearly best, bonus or not
+        let (grid1, grid2, player, die) = POSITIONS[2];
+        let clear = state_from_js(&grid1, &grid2, player, die);
+        let values = plain_values(&clear);
+        let best = pick(&clear, &plain);
+        assert!(values.iter().all(|&(col, value)| col == best || value < value_of(&values, best) - tempo.tempo_bonus), "{values:?}");
+        assert_eq!(pick(&clear, &tempo), best);
+        
+        // The term moves a value by less than the bonus, so it can only switch
+        // between moves that close
+        for state in random_positions(41, 40) {
+            let (from, to) = (pick(&state, &plain), pick(&state, &tempo));
+            let values = plain_values(&state);
+            assert!(value_of(&values, from) - value_of(&values, to) <= tempo.tempo_bonus, "{values:?}");
         }
-        let len2 = grid2.len().min(9);
-        for i in 0..len2 {
-            state.grid2.data[i] = grid2[i];
+    }
+    
+    #[cfg(feature = "debug")]
+    #[wasm_bindgen_test]
+    fn debug_search_logs_every_root_move_with_its_value() {
+        for (grid1, grid2, player, die) in POSITIONS {
+            let state = state_from_js(&grid1, &grid2, player, die);
+            let config = DifficultyConfig { depth: 3, advanced_eval: true, adversarial: true, ..DifficultyConfig::default() };
+            let mut ctx = test_ctx();
+            ctx.use_adversarial = true;
+            let expected = evaluate_root_moves(&state, state.current_player, &config, &config, &mut ctx);
+            
+            let log = AIEngine::new().debug_search(&grid1, &grid2, player, die, 3);
+            let logged: Vec<(usize, f64)> = log.lines()
+                .filter_map(|line| line.strip_prefix("root col "))
+                .map(|rest| {
+                    let (col, rest) = rest.split_once(": value ").unwrap();
+                    let value = rest.split_whitespace().next().unwrap();
+                    (col.parse().unwrap(), value.parse().unwrap())
+                })
+                .collect();
+            assert_eq!(logged.len(), expected.len(), "{log}");
+            for (&(col, value), &(expected_col, expected_value)) in logged.iter().zip(&expected) {
+                assert_eq!(col, expected_col);
+                assert!((value - expected_value).abs() < 1e-3, "{log}");
+            }
+            assert!(log.lines().last().unwrap().starts_with("best: col "));
         }
-        for i in len2..9 {
-            state.grid2.data[i] = 0;
+    }
+    
+    #[wasm_bindgen_test]
+    fn should_accept_draw_needs_a_level_and_risky_position() {
+        let mut engine = AIEngine::new();
+        // Dead level, with the opponent's roll swinging the margin by about a point
+        let (level, level_opp) = ([1, 0, 0, 0, 0, 0, 0, 0, 0], [1, 0, 0, 0, 0, 0, 0, 0, 0]);
+        assert!(!engine.should_accept_draw(&level, &level_opp, 0, 1, 2));
+        engine.set_draw_tolerance(1.0, 1.0);
+        assert!(engine.should_accept_draw(&level, &level_opp, 0, 1, 2));
+        // Four points up with the board nearly settled is no time to agree a draw
+        assert!(!engine.should_accept_draw(&[1, 2, 3, 1, 2, 3, 1, 2, 0], &[1, 2, 3, 1, 2, 3, 2, 0, 0], 0, 1, 2));
+        // Nor when the best move finishes the game, however loose the thresholds
+        engine.set_draw_tolerance(5.0, 0.0);
+        assert!(!engine.should_accept_draw(&[1, 2, 3, 1, 2, 3, 1, 2, 0], &[1, 2, 3, 1, 2, 3, 2, 0, 0], 0, 1, 2));
+        assert!(!engine.should_accept_draw(&level, &level_opp, 0, 0, 2));
+    }
+    
+    #[test]
+    fn captures_can_repeat_a_position() {
+        let mut engine = AIEngine::new();
+        let record = |engine: &mut AIEngine, state: &GameState| {
+            engine.record_position(&state.grid1.data, &state.grid2.data, state.current_player as u8)
+        };
+        // Each side keeps answering a 3 with a 3 in the left column, capturing it
+        let start = state_from_js(&[3, 0, 0, 0, 0, 0, 0, 0, 0], &[0; 9], 1, 0);
+        let mut state = start.clone();
+        assert_eq!(record(&mut engine, &state), 1);
+        for cycle in 1..=3 {
+            for _ in 0..2 {
+                state = apply_move(&roll_die(&state, 3), 0).unwrap();
+                record(&mut engine, &state);
+            }
+            assert_eq!(state, start);
+            assert_eq!(engine.position_repeated(), cycle + 1);
         }
+        // The other position in the cycle has been seen once per lap
+        let other = apply_move(&roll_die(&state, 3), 0).unwrap();
+        assert_eq!(record(&mut engine, &other), 4);
         
-        if state.phase != GamePhase::Placing || state.current_die.is_none() {
-            return -1;
+        engine.clear_position_history();
+        assert_eq!(engine.position_repeated(), 0);
+        assert_eq!(record(&mut engine, &start), 1);
+    }
+    
+    /// The (column, value) entries of a `rank_moves` JSON array, in order
+    fn ranked_entries(json: &str) -> Vec<(usize, f64)> {
+        json.split("{\"column\":")
+            .skip(1)
+            .map(|entry| {
+                let (col, rest) = entry.split_once(",\"value\":").unwrap();
+                (col.parse().unwrap(), rest.trim_end_matches([']', '}', ',']).parse().unwrap())
+            })
+            .collect()
+    }
+    
+    #[wasm_bindgen_test]
+    fn static_baseline_plus_best_delta_gives_the_best_value() {
+        let mut engine = AIEngine::new();
+        for (grid1, grid2, player, die) in POSITIONS {
+            let state = state_from_js(&grid1, &grid2, player, die);
+            let baseline = engine.static_baseline(&grid1, &grid2, player, false);
+            let ranked = ranked_entries(&engine.rank_moves(&grid1, &grid2, player, die, 1, 0.5, 0.5, false, 1, 0.0, 0.5, 0.5, false));
+            let (best_col, best_value) = ranked[0];
+            // A one-ply move value is the margin after the placement, so its
+            // delta is what the placement adds and captures
+            let delta = order_score(&state, best_col, die, state.current_player) as f64;
+            assert!((baseline + delta - best_value).abs() < 1e-4, "{baseline} + {delta} vs {best_value}");
         }
         
-        match mcts_search_with_nn(&state, time_budget_ms, &self.network) {
-            Some(col) => col as i32,
-            None => -1,
-        }
+        // A finished board is scored as it stands, and as a loss when advanced
+        assert_eq!(engine.static_baseline(&[1; 9], &[2, 0, 0, 0, 0, 0, 0, 0, 0], 1, false), -25.0);
+        assert!(engine.static_baseline(&[1; 9], &[2, 0, 0, 0, 0, 0, 0, 0, 0], 1, true) < -25.0);
+        assert!(engine.static_baseline(&[1], &[0], 2, false).is_nan());
     }
     
-    /// Get policy and value outputs for a state (for debugging/analysis)
-    #[wasm_bindgen]
-    pub fn get_policy_value(
-        &self,
-        grid1: &[u8],
-        grid2: &[u8],
-        current_player: u8,
-        current_die: u8,
-    ) -> Vec<f64> {
-        let mut state = GameState {
-            grid1: Grid { data: [0; 9] },
-            grid2: Grid { data: [0; 9] },
-            current_player: if current_player == 0 { Player::Player1 } else { Player::Player2 },
-            current_die: if current_die == 0 { None } else { Some(current_die) },
-            phase: if current_die == 0 { GamePhase::Rolling } else { GamePhase::Placing },
-            turn_number: 1,
+    #[wasm_bindgen_test]
+    fn line_sharpness_leaves_the_engine_rolls_alone() {
+        let (grid1, grid2, player, die) = POSITIONS[3];
+        let mut engine = AIEngine::new();
+        engine.set_seed(11);
+        let line = engine.line_sharpness(&grid1, &grid2, player, die, 6);
+        assert_eq!(line.len(), 6);
+        let rolls: Vec<u8> = (0..10).map(|_| engine.next_die()).collect();
+        
+        let mut untouched = AIEngine::new();
+        untouched.set_seed(11);
+        assert_eq!((0..10).map(|_| untouched.next_die()).collect::<Vec<u8>>(), rolls);
+        // The same seed still gives the same line
+        let mut again = AIEngine::new();
+        again.set_seed(11);
+        assert_eq!(again.line_sharpness(&grid1, &grid2, player, die, 6), line);
+    }
+    
+    #[test]
+    fn column_safety_follows_the_opposing_room_and_the_stake() {
+        let engine = AIEngine::new();
+        // Column 0 sits behind a full opposing column, column 1 holds the
+        // highest possible score against an empty column, column 2 is empty
+        let safety = engine.column_safety(&[6, 6, 6, 6, 6, 6, 0, 0, 0], &[1, 2, 3, 0, 0, 0, 0, 0, 0]);
+        assert_eq!(safety, vec![1.0, 0.0, 1.0]);
+        
+        // A smaller stake with less room left against it is safer, but not safe
+        let safety = engine.column_safety(&[0, 0, 0, 5, 5, 0, 0, 0, 0], &[0, 0, 0, 2, 4, 0, 0, 0, 0]);
+        assert!(safety[1] > 0.5 && safety[1] < 1.0, "{safety:?}");
+        assert!(engine.column_safety(&[7], &[0]).is_empty());
+    }
+    
+    #[test]
+    fn win_probability_takes_the_safer_win_in_a_close_endgame() {
+        let state = state_from_js(&[1, 5, 0, 5, 5, 4, 1, 1, 0], &[2, 1, 0, 6, 3, 2, 3, 4, 2], 0, 1);
+        let margin = DifficultyConfig { depth: 6, ..DifficultyConfig::default() };
+        let win = DifficultyConfig { objective: SearchObjective::WinProbability, ..margin };
+        let search = |config: &DifficultyConfig| {
+            let mut ctx = test_ctx();
+            ctx.use_adversarial = true;
+            let values = evaluate_root_moves(&state, Player::Player1, config, config, &mut ctx);
+            restart(&mut ctx);
+            let (col, _) = expectimax_internal(&state, Player::Player1, config, config, &mut ctx);
+            (col, values)
         };
         
-        let len1 = grid1.len().min(9);
-        for i in 0..len1 {
-            state.grid1.data[i] = grid1[i];
+        // Column 0 wins by more on average but can still lose; column 2 can't
+        let (margin_col, margin_values) = search(&margin);
+        let (win_col, win_values) = search(&win);
+        assert_eq!(margin_col, Some(0), "{margin_values:?}");
+        assert_eq!(win_col, Some(2), "{win_values:?}");
+        assert!(margin_values[0].1 > margin_values[1].1, "{margin_values:?}");
+        assert!(win_values[1].1 > 1.0 - 1e-9 && win_values[0].1 < win_values[1].1, "{win_values:?}");
+    }
+    
+    #[wasm_bindgen_test]
+    fn set_objective_switches_to_the_safer_win() {
+        let (grid1, grid2) = ([1, 5, 0, 5, 5, 4, 1, 1, 0], [2, 1, 0, 6, 3, 2, 3, 4, 2]);
+        let mut engine = AIEngine::new();
+        let best = |engine: &mut AIEngine| {
+            engine.get_best_move_extended(&grid1, &grid2, 0, 1, 6, 0.0, 0.5, 0.5, false, true, 0.0, 6, 0.0, 0.5, 0.5, false, true, 0.0)
+        };
+        assert_eq!(best(&mut engine), 0);
+        engine.set_objective(SearchObjective::WinProbability);
+        assert_eq!(best(&mut engine), 2);
+        engine.set_objective(SearchObjective::ExpectedMargin);
+        assert_eq!(best(&mut engine), 0);
+    }
+    
+    #[test]
+    fn capped_search_leaves_no_entries_behind() {
+        let config = DifficultyConfig { depth: 4, advanced_eval: true, ..DifficultyConfig::default() };
+        let opponent = DifficultyConfig { depth: 2, ..config };
+        for (grid1, grid2, player, die) in POSITIONS {
+            let state = state_from_js(&grid1, &grid2, player, die);
+            let player = state.current_player;
+            let mut fresh = test_ctx();
+            let expected = expectimax_internal(&state, player, &config, &opponent, &mut fresh);
+            
+            let mut ctx = test_ctx();
+            ctx.max_nodes = 300;
+            expectimax_internal(&state, player, &config, &opponent, &mut ctx);
+            ctx.max_nodes = fresh.max_nodes;
+            restart(&mut ctx);
+            assert_eq!(expectimax_internal(&state, player, &config, &opponent, &mut ctx), expected);
+        }
+    }
+    
+    /// Play one seeded game with both sides searched by the same engine, and
+    /// return the table hits and probes summed over every move
+    fn seeded_game_tt_counts(keep_table: bool) -> (u32, u32) {
+        let mut engine = AIEngine::new();
+        engine.set_seed(7);
+        let mut state = state_from_js(&[0; 9], &[0; 9], 0, 0);
+        let (mut hits, mut probes) = (0, 0);
+        while state.phase != GamePhase::Ended {
+            let die = engine.next_die();
+            if !keep_table {
+                engine.clear_cache();
+            }
+            let player = state.current_player as u8;
+            let col = engine.get_best_move(&state.grid1.data, &state.grid2.data, player, die, 4, 0.0, 0.5, 0.5, true, 4, 0.0, 0.5, 0.5, true);
+            let stats = engine.last_search_stats();
+            hits += stats.get_tt_hits();
+            probes += stats.get_tt_probes();
+            state = apply_move(&roll_die(&state, die), col as usize).unwrap();
         }
-        for i in len1..9 {
-            state.grid1.data[i] = 0;
+        (hits, probes)
+    }
+    
+    #[wasm_bindgen_test]
+    fn kept_table_raises_hit_rate_over_a_game() {
+        let (cold_hits, cold_probes) = seeded_game_tt_counts(false);
+        let (warm_hits, warm_probes) = seeded_game_tt_counts(true);
+        let cold_rate = cold_hits as f64 / cold_probes as f64;
+        let warm_rate = warm_hits as f64 / warm_probes as f64;
+        assert!(warm_rate > cold_rate, "{warm_rate} vs {cold_rate}");
+    }
+    
+    /// An engine whose table holds entries from adversarial, advanced-eval
+    /// `depth` move searches for each side of `grid1`/`grid2` and every roll
+    fn engine_after_move_search(grid1: &[u8], grid2: &[u8], depth: u32) -> AIEngine {
+        let mut engine = AIEngine::new();
+        for player in 0..2 {
+            for die in 1..=6 {
+                engine.get_best_move_extended(grid1, grid2, player, die, depth, 0.0, 0.8, 0.2, true, true, 0.0, depth, 0.0, 0.8, 0.2, true, true, 0.0);
+            }
         }
-        let len2 = grid2.len().min(9);
-        for i in 0..len2 {
-            state.grid2.data[i] = grid2[i];
+        engine
+    }
+    
+    #[wasm_bindgen_test]
+    fn fixed_depth_analysis_ignores_earlier_move_searches() {
+        for (grid1, grid2, player, die) in POSITIONS {
+            // Every fixed-depth analysis starts from begin_fixed_search, which
+            // drops what the move searches left in the table
+            let mut engine = engine_after_move_search(&grid1, &grid2, 3);
+            assert!(!engine.ctx.tt.is_empty());
+            engine.ctx.begin_fixed_search(false);
+            assert!(engine.ctx.tt.is_empty());
+            
+            let fresh = AIEngine::new().move_confidence(&grid1, &grid2, player, die, 3);
+            let mut engine = engine_after_move_search(&grid1, &grid2, 3);
+            assert_eq!(engine.move_confidence(&grid1, &grid2, player, die, 3), fresh);
         }
-        for i in len2..9 {
-            state.grid2.data[i] = 0;
+    }
+    
+    #[wasm_bindgen_test]
+    fn move_confidence_tracks_the_gap_to_the_second_move() {
+        let mut engine = AIEngine::new();
+        // Capturing the opposing column of sixes stands well clear of the rest
+        let clear = engine.move_confidence(&[0; 9], &[6, 6, 6, 0, 0, 0, 0, 0, 0], 0, 6, 2);
+        assert!(clear > 0.9, "{clear}");
+        // On an empty board every column is the same move
+        let even = engine.move_confidence(&[0; 9], &[0; 9], 0, 4, 2);
+        assert!(even < 0.05, "{even}");
+        assert!(engine.move_confidence(&[0; 9], &[0; 9], 0, 7, 2).is_nan());
+    }
+    
+    #[test]
+    fn pruned_search_matches_the_reference_expectimax() {
+        let states: Vec<GameState> = POSITIONS.iter()
+            .map(|(grid1, grid2, player, die)| state_from_js(grid1, grid2, *player, *die))
+            .chain(random_positions(21, 8))
+            .collect();
+        for advanced_eval in [false, true] {
+            let config = DifficultyConfig { depth: 3, advanced_eval, ..DifficultyConfig::default() };
+            // A minimizing opponent, then greedy and searching opponent models
+            let opponents = [(config, true), (DifficultyConfig { depth: 0, ..config }, false), (DifficultyConfig { depth: 2, ..config }, false)];
+            for (opponent, adversarial) in opponents {
+                for state in &states {
+                    let player = state.current_player;
+                    let reference = reference_root_values(state, &config, &opponent, adversarial);
+                    let mut ctx = test_ctx();
+                    ctx.use_adversarial = adversarial;
+                    let values = evaluate_root_moves(state, player, &config, &opponent, &mut ctx);
+                    assert_eq!(values.len(), reference.len());
+                    for (&(col, value), &(reference_col, reference_value)) in values.iter().zip(&reference) {
+                        assert_eq!(col, reference_col);
+                        assert!((value - reference_value).abs() < 1e-9, "{state:?} col {col}: {value} vs {reference_value}");
+                    }
+                    
+                    let mut ctx = test_ctx();
+                    ctx.use_adversarial = adversarial;
+                    let (col, value) = expectimax_internal(state, player, &config, &opponent, &mut ctx);
+                    let best = reference.iter().map(|&(_, value)| value).fold(f64::NEG_INFINITY, f64::max);
+                    assert!((value - best).abs() < 1e-9, "{state:?}: {value} vs {best}");
+                    assert!(reference.iter().any(|&(reference_col, value)| Some(reference_col) == col && (value - best).abs() < 1e-9));
+                }
+            }
         }
+    }
+    
+    #[wasm_bindgen_test]
+    fn rank_moves_leads_with_the_move_the_root_tie_rules_pick() {
+        // The equal-move window settles on the right column, a point below the best value
+        let (grid1, grid2) = ([2, 6, 0, 2, 0, 0, 0, 0, 0], [4, 0, 0, 0, 0, 0, 2, 0, 0]);
+        let mut engine = AIEngine::new();
+        engine.set_equal_move_epsilon(1.5, false);
+        let best = engine.get_best_move(&grid1, &grid2, 0, 2, 2, 0.0, 0.5, 0.5, false, 2, 0.0, 0.5, 0.5, false);
+        let ranked = ranked_entries(&engine.rank_moves(&grid1, &grid2, 0, 2, 2, 0.5, 0.5, false, 2, 0.0, 0.5, 0.5, false));
+        assert_eq!(best, 2);
+        assert_eq!(ranked.len(), 3);
+        assert_eq!(ranked[0].0, 2);
+        assert!(ranked[1].1 > ranked[0].1 && ranked[1].1 >= ranked[2].1, "{ranked:?}");
         
-        let (policy, value) = self.network.forward(&state);
+        // The basic tiebreak settles a tie on the advanced evaluation
+        let (grid1, grid2) = ([1, 4, 1, 6, 1, 0, 3, 6, 0], [6, 4, 1, 5, 0, 0, 4, 2, 2]);
+        let mut engine = AIEngine::new();
+        engine.set_basic_tiebreak(true);
+        let best = engine.get_best_move(&grid1, &grid2, 0, 4, 1, 0.0, 0.5, 0.5, true, 1, 0.0, 0.5, 0.5, true);
+        let ranked = ranked_entries(&engine.rank_moves(&grid1, &grid2, 0, 4, 1, 0.5, 0.5, true, 1, 0.0, 0.5, 0.5, true));
+        assert_eq!(best, 2);
+        assert_eq!(ranked[0].0, 2);
         
-        // Return [policy[0], policy[1], policy[2], value]
-        vec![policy[0], policy[1], policy[2], value]
+        // Without tie rules the list stays in value order
+        for (grid1, grid2, player, die) in POSITIONS {
+            let mut engine = AIEngine::new();
+            let best = engine.get_best_move(&grid1, &grid2, player, die, 3, 0.0, 0.5, 0.5, true, 2, 0.0, 0.5, 0.5, true);
+            let ranked = ranked_entries(&engine.rank_moves(&grid1, &grid2, player, die, 3, 0.5, 0.5, true, 2, 0.0, 0.5, 0.5, true));
+            assert_eq!(ranked[0].0 as i32, best);
+            assert!(ranked.windows(2).all(|pair| pair[0].1 >= pair[1].1), "{ranked:?}");
+        }
+    }
+    
+    #[test]
+    fn exact_root_search_picks_the_same_move_with_full_values() {
+        let config = DifficultyConfig { depth: 3, advanced_eval: true, ..DifficultyConfig::default() };
+        let settled = DifficultyConfig { equal_move_epsilon: 1.5, ..config };
+        for player_config in [config, settled] {
+            for state in random_positions(17, 10) {
+                let player = state.current_player;
+                let (picked, _) = expectimax_internal(&state, player, &player_config, &config, &mut test_ctx());
+                let (exact_pick, _, mut values) = search_root(&state, player, &player_config, &config, &mut test_ctx(), true);
+                assert_eq!(exact_pick, picked);
+                values.sort_by_key(|&(col, _)| col);
+                let full = evaluate_root_moves(&state, player, &player_config, &config, &mut test_ctx());
+                assert_eq!(values.len(), full.len());
+                for (&(col, value), &(full_col, full_value)) in values.iter().zip(&full) {
+                    assert_eq!(col, full_col);
+                    assert!((value - full_value).abs() < 1e-9, "col {col}: {value} vs {full_value}");
+                }
+            }
+        }
     }
 }